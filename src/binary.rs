@@ -1,5 +1,9 @@
+pub mod disasm;
+pub mod encode;
+pub mod error;
 pub mod instruction;
 pub mod module;
 pub mod opcode;
 pub mod section;
 pub mod types;
+pub mod validate;
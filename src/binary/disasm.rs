@@ -0,0 +1,477 @@
+use super::{
+    instruction::Instruction,
+    module::Module,
+    types::{BlockType, ExportDesc, FuncType, ImportDesc},
+};
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+};
+use core::fmt::Write as _;
+
+// Moduleを.watに似たテキスト形式で出力する(デバッグ用)
+pub fn disassemble(module: &Module) -> String {
+    let mut out = String::new();
+    let types = module.type_section.as_deref().unwrap_or_default();
+    let no_names = BTreeMap::new();
+    let names = module.name_section.as_ref().unwrap_or(&no_names);
+
+    out.push_str("(module\n");
+    let mut func_idx = 0;
+    for import in module.import_section.iter().flatten() {
+        if let ImportDesc::Func(type_idx) = import.desc {
+            let _ = write!(
+                out,
+                "  (import \"{}\" \"{}\" (func {}",
+                import.module,
+                import.field,
+                func_name(names, func_idx)
+            );
+            if let Some(func_type) = types.get(type_idx as usize) {
+                write_func_type(&mut out, func_type);
+            }
+            out.push_str("))\n");
+            func_idx += 1;
+        }
+    }
+
+    let type_idxs = module.function_section.as_deref().unwrap_or_default();
+    let funcs = module.code_section.as_deref().unwrap_or_default();
+    for (type_idx, func) in type_idxs.iter().zip(funcs) {
+        let _ = write!(out, "  (func {}", func_name(names, func_idx));
+        if let Some(func_type) = types.get(*type_idx as usize) {
+            write_func_type(&mut out, func_type);
+        }
+        out.push('\n');
+
+        for local in func.locals.iter() {
+            for _ in 0..local.type_count {
+                let _ = writeln!(out, "    (local {})", local.value_type);
+            }
+        }
+
+        let mut depth: usize = 0;
+        // 最後のendは関数本体の終わりなので出力しない
+        let body = func
+            .code
+            .strip_suffix(&[Instruction::End])
+            .unwrap_or(&func.code);
+        for inst in body {
+            // 検証していないモジュールでは対応しないendがありうる
+            if matches!(inst, Instruction::End | Instruction::Else) {
+                depth = depth.saturating_sub(1);
+            }
+            let _ = writeln!(
+                out,
+                "    {}{}",
+                "  ".repeat(depth),
+                format_instruction(inst, names)
+            );
+            if matches!(
+                inst,
+                Instruction::Block(_)
+                    | Instruction::Loop(_)
+                    | Instruction::If(_)
+                    | Instruction::Else
+            ) {
+                depth += 1;
+            }
+        }
+        out.push_str("  )\n");
+        func_idx += 1;
+    }
+
+    for export in module.export_section.iter().flatten() {
+        let desc = match export.desc {
+            ExportDesc::Func(idx) => format!("func {}", func_name(names, idx)),
+            ExportDesc::Table(idx) => format!("table {}", idx),
+            ExportDesc::Memory(idx) => format!("memory {}", idx),
+            ExportDesc::Global(idx) => format!("global {}", idx),
+        };
+        let _ = writeln!(out, "  (export \"{}\" ({}))", export.name, desc);
+    }
+    out.push_str(")\n");
+    out
+}
+
+// nameセクションに名前があればそれを、なければインデックスを使う
+fn func_name(names: &BTreeMap<u32, String>, idx: u32) -> String {
+    match names.get(&idx) {
+        Some(name) => format!("${}", name),
+        None => format!("${}", idx),
+    }
+}
+
+fn write_func_type(out: &mut String, func_type: &FuncType) {
+    if !func_type.params.is_empty() {
+        out.push_str(" (param");
+        for param in func_type.params.iter() {
+            let _ = write!(out, " {}", param);
+        }
+        out.push(')');
+    }
+    if !func_type.results.is_empty() {
+        out.push_str(" (result");
+        for result in func_type.results.iter() {
+            let _ = write!(out, " {}", result);
+        }
+        out.push(')');
+    }
+}
+
+fn format_block_type(name: &str, block_type: &BlockType) -> String {
+    match block_type {
+        BlockType::Void => name.to_string(),
+        BlockType::Value(value_type) => format!("{} (result {})", name, value_type),
+        BlockType::TypeIndex(idx) => format!("{} (type {})", name, idx),
+    }
+}
+
+fn format_instruction(inst: &Instruction, names: &BTreeMap<u32, String>) -> String {
+    match inst {
+        Instruction::Block(block_type) => format_block_type("block", block_type),
+        Instruction::Loop(block_type) => format_block_type("loop", block_type),
+        Instruction::If(block_type) => format_block_type("if", block_type),
+        Instruction::Br(idx) => format!("br {}", idx),
+        Instruction::BrIf(idx) => format!("br_if {}", idx),
+        Instruction::BrTable(table) => {
+            let mut text = "br_table".to_string();
+            for target in table.targets.iter().chain([&table.default]) {
+                let _ = write!(text, " {}", target);
+            }
+            text
+        }
+        Instruction::Call(idx) => format!("call {}", func_name(names, *idx)),
+        Instruction::CallIndirect {
+            type_idx,
+            table_idx,
+        } => format!("call_indirect {} (type {})", table_idx, type_idx),
+        Instruction::LocalGet(idx) => format!("local.get {}", idx),
+        Instruction::LocalSet(idx) => format!("local.set {}", idx),
+        Instruction::LocalTee(idx) => format!("local.tee {}", idx),
+        Instruction::GlobalGet(idx) => format!("global.get {}", idx),
+        Instruction::GlobalSet(idx) => format!("global.set {}", idx),
+        Instruction::TableGet(idx) => format!("table.get {}", idx),
+        Instruction::TableSet(idx) => format!("table.set {}", idx),
+        Instruction::I32Const(value) => format!("i32.const {}", value),
+        Instruction::I64Const(value) => format!("i64.const {}", value),
+        Instruction::F32Const(value) => format!("f32.const {}", value),
+        Instruction::F64Const(value) => format!("f64.const {}", value),
+        Instruction::RefNull(_) => "ref.null func".to_string(),
+        Instruction::RefIsNull => "ref.is_null".to_string(),
+        Instruction::RefFunc(idx) => format!("ref.func {}", func_name(names, *idx)),
+        Instruction::MemoryInit(idx) => format!("memory.init {}", idx),
+        Instruction::DataDrop(idx) => format!("data.drop {}", idx),
+        Instruction::MemoryCopy => "memory.copy".to_string(),
+        Instruction::MemoryFill => "memory.fill".to_string(),
+        Instruction::I32Load { align, offset }
+        | Instruction::I64Load { align, offset }
+        | Instruction::I32Load8S { align, offset }
+        | Instruction::I32Load8U { align, offset }
+        | Instruction::I32Load16S { align, offset }
+        | Instruction::I32Load16U { align, offset }
+        | Instruction::I64Load8S { align, offset }
+        | Instruction::I64Load8U { align, offset }
+        | Instruction::I64Load16S { align, offset }
+        | Instruction::I64Load16U { align, offset }
+        | Instruction::I64Load32S { align, offset }
+        | Instruction::I64Load32U { align, offset }
+        | Instruction::I32Store { align, offset }
+        | Instruction::I64Store { align, offset }
+        | Instruction::I32Store8 { align, offset }
+        | Instruction::I32Store16 { align, offset }
+        | Instruction::I64Store8 { align, offset }
+        | Instruction::I64Store16 { align, offset }
+        | Instruction::I64Store32 { align, offset } => {
+            format!("{}{}", mnemonic(inst), memarg_text(*align, *offset))
+        }
+        _ => mnemonic(inst).to_string(),
+    }
+}
+
+// offset=4, align=2 -> " offset=4 align=4"
+fn memarg_text(align: u32, offset: u32) -> String {
+    let mut text = String::new();
+    if offset != 0 {
+        let _ = write!(text, " offset={}", offset);
+    }
+    let _ = write!(text, " align={}", 1u64 << align.min(63));
+    text
+}
+
+// 仕様で定められた命令名
+fn mnemonic(inst: &Instruction) -> &'static str {
+    match inst {
+        Instruction::Unreachable => "unreachable",
+        Instruction::Nop => "nop",
+        Instruction::Block(_) => "block",
+        Instruction::Loop(_) => "loop",
+        Instruction::If(_) => "if",
+        Instruction::Else => "else",
+        Instruction::End => "end",
+        Instruction::Br(_) => "br",
+        Instruction::BrIf(_) => "br_if",
+        Instruction::BrTable(_) => "br_table",
+        Instruction::Call(_) => "call",
+        Instruction::CallIndirect { .. } => "call_indirect",
+        Instruction::LocalGet(_) => "local.get",
+        Instruction::LocalSet(_) => "local.set",
+        Instruction::LocalTee(_) => "local.tee",
+        Instruction::GlobalGet(_) => "global.get",
+        Instruction::GlobalSet(_) => "global.set",
+        Instruction::TableGet(_) => "table.get",
+        Instruction::TableSet(_) => "table.set",
+        Instruction::I32Load { .. } => "i32.load",
+        Instruction::I64Load { .. } => "i64.load",
+        Instruction::I32Load8S { .. } => "i32.load8_s",
+        Instruction::I32Load8U { .. } => "i32.load8_u",
+        Instruction::I32Load16S { .. } => "i32.load16_s",
+        Instruction::I32Load16U { .. } => "i32.load16_u",
+        Instruction::I64Load8S { .. } => "i64.load8_s",
+        Instruction::I64Load8U { .. } => "i64.load8_u",
+        Instruction::I64Load16S { .. } => "i64.load16_s",
+        Instruction::I64Load16U { .. } => "i64.load16_u",
+        Instruction::I64Load32S { .. } => "i64.load32_s",
+        Instruction::I64Load32U { .. } => "i64.load32_u",
+        Instruction::I32Store { .. } => "i32.store",
+        Instruction::I64Store { .. } => "i64.store",
+        Instruction::I32Store8 { .. } => "i32.store8",
+        Instruction::I32Store16 { .. } => "i32.store16",
+        Instruction::I64Store8 { .. } => "i64.store8",
+        Instruction::I64Store16 { .. } => "i64.store16",
+        Instruction::I64Store32 { .. } => "i64.store32",
+        Instruction::I32Const(_) => "i32.const",
+        Instruction::I64Const(_) => "i64.const",
+        Instruction::F32Const(_) => "f32.const",
+        Instruction::F64Const(_) => "f64.const",
+        Instruction::I32Eqz => "i32.eqz",
+        Instruction::I32Eq => "i32.eq",
+        Instruction::I32Ne => "i32.ne",
+        Instruction::I32LtS => "i32.lt_s",
+        Instruction::I32LtU => "i32.lt_u",
+        Instruction::I32GtS => "i32.gt_s",
+        Instruction::I32GtU => "i32.gt_u",
+        Instruction::I32LeS => "i32.le_s",
+        Instruction::I32LeU => "i32.le_u",
+        Instruction::I32GeS => "i32.ge_s",
+        Instruction::I32GeU => "i32.ge_u",
+        Instruction::I64Eqz => "i64.eqz",
+        Instruction::I64Eq => "i64.eq",
+        Instruction::I64Ne => "i64.ne",
+        Instruction::I64LtS => "i64.lt_s",
+        Instruction::I64LtU => "i64.lt_u",
+        Instruction::I64GtS => "i64.gt_s",
+        Instruction::I64GtU => "i64.gt_u",
+        Instruction::I64LeS => "i64.le_s",
+        Instruction::I64LeU => "i64.le_u",
+        Instruction::I64GeS => "i64.ge_s",
+        Instruction::I64GeU => "i64.ge_u",
+        Instruction::F32Eq => "f32.eq",
+        Instruction::F32Ne => "f32.ne",
+        Instruction::F32Lt => "f32.lt",
+        Instruction::F32Gt => "f32.gt",
+        Instruction::F32Le => "f32.le",
+        Instruction::F32Ge => "f32.ge",
+        Instruction::F64Eq => "f64.eq",
+        Instruction::F64Ne => "f64.ne",
+        Instruction::F64Lt => "f64.lt",
+        Instruction::F64Gt => "f64.gt",
+        Instruction::F64Le => "f64.le",
+        Instruction::F64Ge => "f64.ge",
+        Instruction::I32Clz => "i32.clz",
+        Instruction::I32Ctz => "i32.ctz",
+        Instruction::I32Popcnt => "i32.popcnt",
+        Instruction::I32Add => "i32.add",
+        Instruction::I32Sub => "i32.sub",
+        Instruction::I32Mul => "i32.mul",
+        Instruction::I32DivS => "i32.div_s",
+        Instruction::I32DivU => "i32.div_u",
+        Instruction::I32RemS => "i32.rem_s",
+        Instruction::I32RemU => "i32.rem_u",
+        Instruction::I32And => "i32.and",
+        Instruction::I32Or => "i32.or",
+        Instruction::I32Xor => "i32.xor",
+        Instruction::I32Shl => "i32.shl",
+        Instruction::I32ShrS => "i32.shr_s",
+        Instruction::I32ShrU => "i32.shr_u",
+        Instruction::I32Rotl => "i32.rotl",
+        Instruction::I32Rotr => "i32.rotr",
+        Instruction::I64Clz => "i64.clz",
+        Instruction::I64Ctz => "i64.ctz",
+        Instruction::I64Popcnt => "i64.popcnt",
+        Instruction::I64Add => "i64.add",
+        Instruction::I64Sub => "i64.sub",
+        Instruction::I64Mul => "i64.mul",
+        Instruction::I64DivS => "i64.div_s",
+        Instruction::I64DivU => "i64.div_u",
+        Instruction::I64RemS => "i64.rem_s",
+        Instruction::I64RemU => "i64.rem_u",
+        Instruction::I64And => "i64.and",
+        Instruction::I64Or => "i64.or",
+        Instruction::I64Xor => "i64.xor",
+        Instruction::I64Shl => "i64.shl",
+        Instruction::I64ShrS => "i64.shr_s",
+        Instruction::I64ShrU => "i64.shr_u",
+        Instruction::I64Rotl => "i64.rotl",
+        Instruction::I64Rotr => "i64.rotr",
+        Instruction::F32Abs => "f32.abs",
+        Instruction::F32Neg => "f32.neg",
+        Instruction::F32Ceil => "f32.ceil",
+        Instruction::F32Floor => "f32.floor",
+        Instruction::F32Trunc => "f32.trunc",
+        Instruction::F32Nearest => "f32.nearest",
+        Instruction::F32Sqrt => "f32.sqrt",
+        Instruction::F32Add => "f32.add",
+        Instruction::F32Sub => "f32.sub",
+        Instruction::F32Mul => "f32.mul",
+        Instruction::F32Div => "f32.div",
+        Instruction::F64Abs => "f64.abs",
+        Instruction::F64Neg => "f64.neg",
+        Instruction::F64Ceil => "f64.ceil",
+        Instruction::F64Floor => "f64.floor",
+        Instruction::F64Trunc => "f64.trunc",
+        Instruction::F64Nearest => "f64.nearest",
+        Instruction::F64Sqrt => "f64.sqrt",
+        Instruction::F64Add => "f64.add",
+        Instruction::F64Sub => "f64.sub",
+        Instruction::F64Mul => "f64.mul",
+        Instruction::F64Div => "f64.div",
+        Instruction::I32WrapI64 => "i32.wrap_i64",
+        Instruction::I32TruncF32S => "i32.trunc_f32_s",
+        Instruction::I32TruncF32U => "i32.trunc_f32_u",
+        Instruction::I32TruncF64S => "i32.trunc_f64_s",
+        Instruction::I32TruncF64U => "i32.trunc_f64_u",
+        Instruction::I64ExtendI32S => "i64.extend_i32_s",
+        Instruction::I64ExtendI32U => "i64.extend_i32_u",
+        Instruction::I64TruncF32S => "i64.trunc_f32_s",
+        Instruction::I64TruncF32U => "i64.trunc_f32_u",
+        Instruction::I64TruncF64S => "i64.trunc_f64_s",
+        Instruction::I64TruncF64U => "i64.trunc_f64_u",
+        Instruction::F32ConvertI32S => "f32.convert_i32_s",
+        Instruction::F32ConvertI32U => "f32.convert_i32_u",
+        Instruction::F32ConvertI64S => "f32.convert_i64_s",
+        Instruction::F32ConvertI64U => "f32.convert_i64_u",
+        Instruction::F32DemoteF64 => "f32.demote_f64",
+        Instruction::F64ConvertI32S => "f64.convert_i32_s",
+        Instruction::F64ConvertI32U => "f64.convert_i32_u",
+        Instruction::F64ConvertI64S => "f64.convert_i64_s",
+        Instruction::F64ConvertI64U => "f64.convert_i64_u",
+        Instruction::F64PromoteF32 => "f64.promote_f32",
+        Instruction::I32ReinterpretF32 => "i32.reinterpret_f32",
+        Instruction::I64ReinterpretF64 => "i64.reinterpret_f64",
+        Instruction::F32ReinterpretI32 => "f32.reinterpret_i32",
+        Instruction::F64ReinterpretI64 => "f64.reinterpret_i64",
+        Instruction::I32Extend8S => "i32.extend8_s",
+        Instruction::I32Extend16S => "i32.extend16_s",
+        Instruction::I64Extend8S => "i64.extend8_s",
+        Instruction::I64Extend16S => "i64.extend16_s",
+        Instruction::I64Extend32S => "i64.extend32_s",
+        Instruction::RefNull(_) => "ref.null",
+        Instruction::RefIsNull => "ref.is_null",
+        Instruction::RefFunc(_) => "ref.func",
+        Instruction::I32TruncSatF32S => "i32.trunc_sat_f32_s",
+        Instruction::I32TruncSatF32U => "i32.trunc_sat_f32_u",
+        Instruction::I32TruncSatF64S => "i32.trunc_sat_f64_s",
+        Instruction::I32TruncSatF64U => "i32.trunc_sat_f64_u",
+        Instruction::I64TruncSatF32S => "i64.trunc_sat_f32_s",
+        Instruction::I64TruncSatF32U => "i64.trunc_sat_f32_u",
+        Instruction::I64TruncSatF64S => "i64.trunc_sat_f64_s",
+        Instruction::I64TruncSatF64U => "i64.trunc_sat_f64_u",
+        Instruction::MemoryInit(_) => "memory.init",
+        Instruction::DataDrop(_) => "data.drop",
+        Instruction::MemoryCopy => "memory.copy",
+        Instruction::MemoryFill => "memory.fill",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{disassemble, mnemonic};
+    use crate::binary::{
+        instruction::Instruction, module::Module, section::Function, types::FuncType,
+    };
+    use alloc::vec;
+    #[cfg(feature = "std")]
+    use anyhow::Result;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn disassemble_func_add() -> Result<()> {
+        let module = Module::from_file("src/fixtures/func_add.wat")?;
+        let text = disassemble(&module);
+        assert_eq!(
+            text,
+            "(module\n  (func $0 (param i32 i32) (result i32)\n    local.get 0\n    local.get 1\n    i32.add\n  )\n)\n"
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn disassemble_blocks() -> Result<()> {
+        let module = Module::from_file("src/fixtures/if_else.wat")?;
+        let text = disassemble(&module);
+        assert!(text.contains("if (result i32)"), "{text}");
+        assert!(text.contains("\n    else\n"), "{text}");
+
+        let module = Module::from_file("src/fixtures/memory_i32.wat")?;
+        let text = disassemble(&module);
+        assert!(text.contains("i32.store"), "{text}");
+        assert!(text.contains("(export "), "{text}");
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn disassemble_with_names() -> Result<()> {
+        let module = Module::from_file("src/fixtures/func_fib.wat")?;
+        let text = disassemble(&module);
+        assert!(
+            text.contains("(func $fib (param i32) (result i32)"),
+            "{text}"
+        );
+        assert!(text.contains("call $fib"), "{text}");
+        assert!(text.contains("(export \"fib\" (func $fib))"), "{text}");
+        Ok(())
+    }
+
+    #[test]
+    fn instruction_mnemonic() {
+        let tests = vec![
+            (Instruction::Unreachable, "unreachable"),
+            (Instruction::I32Add, "i32.add"),
+            (Instruction::I64ShrU, "i64.shr_u"),
+            (Instruction::I32TruncF32S, "i32.trunc_f32_s"),
+            (Instruction::I64Extend32S, "i64.extend32_s"),
+            (Instruction::F64PromoteF32, "f64.promote_f32"),
+            (Instruction::I32TruncSatF64U, "i32.trunc_sat_f64_u"),
+            (
+                Instruction::I32Load8U {
+                    align: 0,
+                    offset: 0,
+                },
+                "i32.load8_u",
+            ),
+        ];
+        for (inst, want) in tests {
+            assert_eq!(mnemonic(&inst), want);
+        }
+    }
+
+    #[test]
+    fn disassemble_unmatched_end() {
+        // 検証していないモジュールの対応しないendでもpanicしない
+        let module = Module {
+            type_section: Some(vec![FuncType::default()]),
+            function_section: Some(vec![0]),
+            code_section: Some(vec![Function {
+                locals: vec![],
+                code: vec![Instruction::End, Instruction::Else, Instruction::End],
+            }]),
+            ..Default::default()
+        };
+        let text = disassemble(&module);
+        assert!(text.contains("    end\n    else\n"), "{text}");
+    }
+}
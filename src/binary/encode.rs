@@ -0,0 +1,722 @@
+use super::{
+    instruction::Instruction,
+    module::Module,
+    opcode::{MiscOpcode, Opcode},
+    section::{Function, SectionCode},
+    types::{
+        BlockType, ConstExpr, Data, DataMode, ElemType, Element, ElementMode, Export, ExportDesc,
+        FuncType, Global, GlobalType, Import, ImportDesc, Limits, Memory, Table, ValueType,
+    },
+};
+use alloc::{collections::BTreeMap, string::String, vec, vec::Vec};
+
+impl Module {
+    // デコードと逆の手順でバイナリ形式に変換する(Custom Sectionは"name"だけ出力する)
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = vec![];
+        buf.extend_from_slice(b"\0asm");
+        buf.extend_from_slice(&self.version.to_le_bytes());
+
+        if let Some(ref types) = self.type_section {
+            encode_section(&mut buf, SectionCode::Type, |buf| {
+                encode_vec(buf, types, encode_func_type)
+            });
+        }
+        if let Some(ref imports) = self.import_section {
+            encode_section(&mut buf, SectionCode::Import, |buf| {
+                encode_vec(buf, imports, encode_import)
+            });
+        }
+        if let Some(ref idxs) = self.function_section {
+            encode_section(&mut buf, SectionCode::Function, |buf| {
+                encode_vec(buf, idxs, |buf, idx| encode_u32(buf, *idx))
+            });
+        }
+        if let Some(ref tables) = self.table_section {
+            encode_section(&mut buf, SectionCode::Table, |buf| {
+                encode_vec(buf, tables, encode_table)
+            });
+        }
+        if let Some(ref memories) = self.memory_section {
+            encode_section(&mut buf, SectionCode::Memory, |buf| {
+                encode_vec(buf, memories, encode_memory)
+            });
+        }
+        if let Some(ref globals) = self.global_section {
+            encode_section(&mut buf, SectionCode::Global, |buf| {
+                encode_vec(buf, globals, encode_global)
+            });
+        }
+        if let Some(ref exports) = self.export_section {
+            encode_section(&mut buf, SectionCode::Export, |buf| {
+                encode_vec(buf, exports, encode_export)
+            });
+        }
+        if let Some(idx) = self.start_section {
+            encode_section(&mut buf, SectionCode::Start, |buf| encode_u32(buf, idx));
+        }
+        if let Some(ref elements) = self.element_section {
+            encode_section(&mut buf, SectionCode::Element, |buf| {
+                encode_vec(buf, elements, encode_element)
+            });
+        }
+        if let Some(count) = self.data_count_section {
+            encode_section(&mut buf, SectionCode::DataCount, |buf| {
+                encode_u32(buf, count)
+            });
+        }
+        if let Some(ref funcs) = self.code_section {
+            encode_section(&mut buf, SectionCode::Code, |buf| {
+                encode_vec(buf, funcs, encode_function)
+            });
+        }
+        if let Some(ref datas) = self.data_section {
+            encode_section(&mut buf, SectionCode::Data, |buf| {
+                encode_vec(buf, datas, encode_data)
+            });
+        }
+        if let Some(ref names) = self.name_section {
+            encode_section(&mut buf, SectionCode::Custom, |buf| {
+                encode_name(buf, "name");
+                encode_name_section(buf, names)
+            });
+        }
+        buf
+    }
+}
+
+// セクションコードとサイズに続けて中身を書き込む
+fn encode_section(buf: &mut Vec<u8>, code: SectionCode, f: impl FnOnce(&mut Vec<u8>)) {
+    let mut contents = vec![];
+    f(&mut contents);
+    buf.push(code as u8);
+    encode_u32(buf, contents.len() as u32);
+    buf.extend(contents);
+}
+
+// 関数名のサブセクション(id 1)をインデックス順に書き込む
+fn encode_name_section(buf: &mut Vec<u8>, names: &BTreeMap<u32, String>) {
+    let names: Vec<_> = names.iter().collect();
+    let mut contents = vec![];
+    encode_vec(&mut contents, &names, |buf, (idx, name)| {
+        encode_u32(buf, **idx);
+        encode_name(buf, name);
+    });
+    buf.push(1);
+    encode_u32(buf, contents.len() as u32);
+    buf.extend(contents);
+}
+
+// 要素数に続けて各要素を書き込む
+fn encode_vec<T>(buf: &mut Vec<u8>, items: &[T], f: impl Fn(&mut Vec<u8>, &T)) {
+    encode_u32(buf, items.len() as u32);
+    for item in items {
+        f(buf, item);
+    }
+}
+
+fn encode_u32(buf: &mut Vec<u8>, mut value: u32) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            buf.push(byte);
+            return;
+        }
+        buf.push(byte | 0x80);
+    }
+}
+
+fn encode_i64(buf: &mut Vec<u8>, mut value: i64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        // 算術シフトなので負の数は-1に収束する
+        value >>= 7;
+        let done = (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0);
+        if done {
+            buf.push(byte);
+            return;
+        }
+        buf.push(byte | 0x80);
+    }
+}
+
+fn encode_i32(buf: &mut Vec<u8>, value: i32) {
+    encode_i64(buf, value as i64);
+}
+
+fn encode_name(buf: &mut Vec<u8>, name: &str) {
+    encode_u32(buf, name.len() as u32);
+    buf.extend_from_slice(name.as_bytes());
+}
+
+fn encode_value_type(buf: &mut Vec<u8>, value_type: &ValueType) {
+    let byte = match value_type {
+        ValueType::I32 => 0x7F,
+        ValueType::I64 => 0x7E,
+        ValueType::F32 => 0x7D,
+        ValueType::F64 => 0x7C,
+        ValueType::FuncRef => 0x70,
+    };
+    buf.push(byte);
+}
+
+fn encode_func_type(buf: &mut Vec<u8>, func_type: &FuncType) {
+    buf.push(0x60);
+    encode_vec(buf, &func_type.params, encode_value_type);
+    encode_vec(buf, &func_type.results, encode_value_type);
+}
+
+fn encode_limits(buf: &mut Vec<u8>, limits: &Limits) {
+    match limits.max {
+        None => {
+            buf.push(0x00);
+            encode_u32(buf, limits.min);
+        }
+        Some(max) => {
+            buf.push(0x01);
+            encode_u32(buf, limits.min);
+            encode_u32(buf, max);
+        }
+    }
+}
+
+fn encode_table(buf: &mut Vec<u8>, table: &Table) {
+    match table.elem_type {
+        ElemType::FuncRef => buf.push(0x70),
+    }
+    encode_limits(buf, &table.limits);
+}
+
+fn encode_memory(buf: &mut Vec<u8>, memory: &Memory) {
+    encode_limits(buf, &memory.limits);
+}
+
+fn encode_global_type(buf: &mut Vec<u8>, global_type: &GlobalType) {
+    encode_value_type(buf, &global_type.value_type);
+    buf.push(global_type.mutable as u8);
+}
+
+fn encode_const_expr(buf: &mut Vec<u8>, expr: &ConstExpr) {
+    match expr {
+        ConstExpr::I32Const(value) => {
+            buf.push(Opcode::I32Const as u8);
+            encode_i32(buf, *value);
+        }
+        ConstExpr::I64Const(value) => {
+            buf.push(Opcode::I64Const as u8);
+            encode_i64(buf, *value);
+        }
+        ConstExpr::F32Const(value) => {
+            buf.push(Opcode::F32Const as u8);
+            buf.extend_from_slice(&value.to_bits().to_le_bytes());
+        }
+        ConstExpr::F64Const(value) => {
+            buf.push(Opcode::F64Const as u8);
+            buf.extend_from_slice(&value.to_bits().to_le_bytes());
+        }
+        ConstExpr::RefNull(ref_type) => {
+            buf.push(Opcode::RefNull as u8);
+            encode_value_type(buf, ref_type);
+        }
+        ConstExpr::RefFunc(idx) => {
+            buf.push(Opcode::RefFunc as u8);
+            encode_u32(buf, *idx);
+        }
+        ConstExpr::GlobalGet(idx) => {
+            buf.push(Opcode::GlobalGet as u8);
+            encode_u32(buf, *idx);
+        }
+    }
+    buf.push(Opcode::End as u8);
+}
+
+fn encode_import(buf: &mut Vec<u8>, import: &Import) {
+    encode_name(buf, &import.module);
+    encode_name(buf, &import.field);
+    match import.desc {
+        ImportDesc::Func(idx) => {
+            buf.push(0x00);
+            encode_u32(buf, idx);
+        }
+        ImportDesc::Table(ref table) => {
+            buf.push(0x01);
+            encode_table(buf, table);
+        }
+        ImportDesc::Memory(ref memory) => {
+            buf.push(0x02);
+            encode_memory(buf, memory);
+        }
+        ImportDesc::Global(ref global_type) => {
+            buf.push(0x03);
+            encode_global_type(buf, global_type);
+        }
+    }
+}
+
+fn encode_global(buf: &mut Vec<u8>, global: &Global) {
+    encode_global_type(buf, &global.global_type);
+    encode_const_expr(buf, &global.init_expr);
+}
+
+fn encode_export(buf: &mut Vec<u8>, export: &Export) {
+    encode_name(buf, &export.name);
+    let (kind, idx) = match export.desc {
+        ExportDesc::Func(idx) => (0x00, idx),
+        ExportDesc::Table(idx) => (0x01, idx),
+        ExportDesc::Memory(idx) => (0x02, idx),
+        ExportDesc::Global(idx) => (0x03, idx),
+    };
+    buf.push(kind);
+    encode_u32(buf, idx);
+}
+
+fn encode_element(buf: &mut Vec<u8>, element: &Element) {
+    // すべてref.funcなら関数のインデックスを並べ、そうでなければ初期化式を並べる(ビット2)
+    let func_idxs: Option<Vec<u32>> = element
+        .init
+        .iter()
+        .map(|expr| match expr {
+            ConstExpr::RefFunc(idx) => Some(*idx),
+            _ => None,
+        })
+        .collect();
+    let (exprs, kind) = match func_idxs {
+        Some(_) => (0x00, 0x00), // elemkind: funcref
+        None => (0x04, 0x70),    // reftype: funcref
+    };
+
+    // passiveは0x01、declarativeは0x03、activeはテーブル0なら0x00、それ以外は0x02
+    match element.mode {
+        ElementMode::Active {
+            table_idx: 0,
+            ref offset,
+        } => {
+            encode_u32(buf, exprs);
+            encode_const_expr(buf, offset);
+        }
+        ElementMode::Active {
+            table_idx,
+            ref offset,
+        } => {
+            encode_u32(buf, 0x02 | exprs);
+            encode_u32(buf, table_idx);
+            encode_const_expr(buf, offset);
+            buf.push(kind);
+        }
+        ElementMode::Passive => {
+            encode_u32(buf, 0x01 | exprs);
+            buf.push(kind);
+        }
+        ElementMode::Declarative => {
+            encode_u32(buf, 0x03 | exprs);
+            buf.push(kind);
+        }
+    }
+    match func_idxs {
+        Some(idxs) => encode_vec(buf, &idxs, |buf, idx| encode_u32(buf, *idx)),
+        None => encode_vec(buf, &element.init, encode_const_expr),
+    }
+}
+
+fn encode_data(buf: &mut Vec<u8>, data: &Data) {
+    // passiveはフラグ0x01、activeはメモリ0なら0x00、それ以外はメモリを指定する0x02
+    match data.mode {
+        DataMode::Passive => encode_u32(buf, 0x01),
+        DataMode::Active {
+            memory_idx: 0,
+            ref offset,
+        } => {
+            encode_u32(buf, 0x00);
+            encode_const_expr(buf, offset);
+        }
+        DataMode::Active {
+            memory_idx,
+            ref offset,
+        } => {
+            encode_u32(buf, 0x02);
+            encode_u32(buf, memory_idx);
+            encode_const_expr(buf, offset);
+        }
+    }
+    encode_u32(buf, data.init.len() as u32);
+    buf.extend_from_slice(&data.init);
+}
+
+fn encode_function(buf: &mut Vec<u8>, func: &Function) {
+    let mut body = vec![];
+    encode_vec(&mut body, &func.locals, |buf, local| {
+        encode_u32(buf, local.type_count);
+        encode_value_type(buf, &local.value_type);
+    });
+    for inst in func.code.iter() {
+        encode_instruction(&mut body, inst);
+    }
+    // 関数本体のサイズ
+    encode_u32(buf, body.len() as u32);
+    buf.extend(body);
+}
+
+fn encode_block_type(buf: &mut Vec<u8>, block_type: &BlockType) {
+    match block_type {
+        BlockType::Void => buf.push(0x40),
+        BlockType::Value(value_type) => encode_value_type(buf, value_type),
+        // Type Sectionのインデックスは符号付きLEB128
+        BlockType::TypeIndex(idx) => encode_i64(buf, *idx as i64),
+    }
+}
+
+fn encode_instruction(buf: &mut Vec<u8>, inst: &Instruction) {
+    let opcode = match inst {
+        Instruction::Block(block_type) => {
+            buf.push(Opcode::Block as u8);
+            return encode_block_type(buf, block_type);
+        }
+        Instruction::Loop(block_type) => {
+            buf.push(Opcode::Loop as u8);
+            return encode_block_type(buf, block_type);
+        }
+        Instruction::If(block_type) => {
+            buf.push(Opcode::If as u8);
+            return encode_block_type(buf, block_type);
+        }
+        Instruction::Br(idx) => return encode_op_u32(buf, Opcode::Br, *idx),
+        Instruction::BrIf(idx) => return encode_op_u32(buf, Opcode::BrIf, *idx),
+        Instruction::BrTable(table) => {
+            buf.push(Opcode::BrTable as u8);
+            encode_vec(buf, &table.targets, |buf, idx| encode_u32(buf, *idx));
+            return encode_u32(buf, table.default);
+        }
+        Instruction::Call(idx) => return encode_op_u32(buf, Opcode::Call, *idx),
+        Instruction::CallIndirect {
+            type_idx,
+            table_idx,
+        } => {
+            buf.push(Opcode::CallIndirect as u8);
+            encode_u32(buf, *type_idx);
+            return encode_u32(buf, *table_idx);
+        }
+        Instruction::LocalGet(idx) => return encode_op_u32(buf, Opcode::LocalGet, *idx),
+        Instruction::LocalSet(idx) => return encode_op_u32(buf, Opcode::LocalSet, *idx),
+        Instruction::LocalTee(idx) => return encode_op_u32(buf, Opcode::LocalTee, *idx),
+        Instruction::GlobalGet(idx) => return encode_op_u32(buf, Opcode::GlobalGet, *idx),
+        Instruction::GlobalSet(idx) => return encode_op_u32(buf, Opcode::GlobalSet, *idx),
+        Instruction::TableGet(idx) => return encode_op_u32(buf, Opcode::TableGet, *idx),
+        Instruction::TableSet(idx) => return encode_op_u32(buf, Opcode::TableSet, *idx),
+        Instruction::I32Load { align, offset } => {
+            return encode_memarg(buf, Opcode::I32Load, *align, *offset)
+        }
+        Instruction::I64Load { align, offset } => {
+            return encode_memarg(buf, Opcode::I64Load, *align, *offset)
+        }
+        Instruction::I32Load8S { align, offset } => {
+            return encode_memarg(buf, Opcode::I32Load8S, *align, *offset)
+        }
+        Instruction::I32Load8U { align, offset } => {
+            return encode_memarg(buf, Opcode::I32Load8U, *align, *offset)
+        }
+        Instruction::I32Load16S { align, offset } => {
+            return encode_memarg(buf, Opcode::I32Load16S, *align, *offset)
+        }
+        Instruction::I32Load16U { align, offset } => {
+            return encode_memarg(buf, Opcode::I32Load16U, *align, *offset)
+        }
+        Instruction::I64Load8S { align, offset } => {
+            return encode_memarg(buf, Opcode::I64Load8S, *align, *offset)
+        }
+        Instruction::I64Load8U { align, offset } => {
+            return encode_memarg(buf, Opcode::I64Load8U, *align, *offset)
+        }
+        Instruction::I64Load16S { align, offset } => {
+            return encode_memarg(buf, Opcode::I64Load16S, *align, *offset)
+        }
+        Instruction::I64Load16U { align, offset } => {
+            return encode_memarg(buf, Opcode::I64Load16U, *align, *offset)
+        }
+        Instruction::I64Load32S { align, offset } => {
+            return encode_memarg(buf, Opcode::I64Load32S, *align, *offset)
+        }
+        Instruction::I64Load32U { align, offset } => {
+            return encode_memarg(buf, Opcode::I64Load32U, *align, *offset)
+        }
+        Instruction::I32Store { align, offset } => {
+            return encode_memarg(buf, Opcode::I32Store, *align, *offset)
+        }
+        Instruction::I64Store { align, offset } => {
+            return encode_memarg(buf, Opcode::I64Store, *align, *offset)
+        }
+        Instruction::I32Store8 { align, offset } => {
+            return encode_memarg(buf, Opcode::I32Store8, *align, *offset)
+        }
+        Instruction::I32Store16 { align, offset } => {
+            return encode_memarg(buf, Opcode::I32Store16, *align, *offset)
+        }
+        Instruction::I64Store8 { align, offset } => {
+            return encode_memarg(buf, Opcode::I64Store8, *align, *offset)
+        }
+        Instruction::I64Store16 { align, offset } => {
+            return encode_memarg(buf, Opcode::I64Store16, *align, *offset)
+        }
+        Instruction::I64Store32 { align, offset } => {
+            return encode_memarg(buf, Opcode::I64Store32, *align, *offset)
+        }
+        Instruction::I32Const(value) => {
+            buf.push(Opcode::I32Const as u8);
+            return encode_i32(buf, *value);
+        }
+        Instruction::I64Const(value) => {
+            buf.push(Opcode::I64Const as u8);
+            return encode_i64(buf, *value);
+        }
+        // 浮動小数点数はIEEE 754のリトルエンディアン
+        Instruction::F32Const(value) => {
+            buf.push(Opcode::F32Const as u8);
+            return buf.extend_from_slice(&value.to_bits().to_le_bytes());
+        }
+        Instruction::F64Const(value) => {
+            buf.push(Opcode::F64Const as u8);
+            return buf.extend_from_slice(&value.to_bits().to_le_bytes());
+        }
+        Instruction::Unreachable => Opcode::Unreachable,
+        Instruction::Nop => Opcode::Nop,
+        Instruction::Else => Opcode::Else,
+        Instruction::End => Opcode::End,
+        Instruction::I32Eqz => Opcode::I32Eqz,
+        Instruction::I32Eq => Opcode::I32Eq,
+        Instruction::I32Ne => Opcode::I32Ne,
+        Instruction::I32LtS => Opcode::I32LtS,
+        Instruction::I32LtU => Opcode::I32LtU,
+        Instruction::I32GtS => Opcode::I32GtS,
+        Instruction::I32GtU => Opcode::I32GtU,
+        Instruction::I32LeS => Opcode::I32LeS,
+        Instruction::I32LeU => Opcode::I32LeU,
+        Instruction::I32GeS => Opcode::I32GeS,
+        Instruction::I32GeU => Opcode::I32GeU,
+        Instruction::I64Eqz => Opcode::I64Eqz,
+        Instruction::I64Eq => Opcode::I64Eq,
+        Instruction::I64Ne => Opcode::I64Ne,
+        Instruction::I64LtS => Opcode::I64LtS,
+        Instruction::I64LtU => Opcode::I64LtU,
+        Instruction::I64GtS => Opcode::I64GtS,
+        Instruction::I64GtU => Opcode::I64GtU,
+        Instruction::I64LeS => Opcode::I64LeS,
+        Instruction::I64LeU => Opcode::I64LeU,
+        Instruction::I64GeS => Opcode::I64GeS,
+        Instruction::I64GeU => Opcode::I64GeU,
+        Instruction::F32Eq => Opcode::F32Eq,
+        Instruction::F32Ne => Opcode::F32Ne,
+        Instruction::F32Lt => Opcode::F32Lt,
+        Instruction::F32Gt => Opcode::F32Gt,
+        Instruction::F32Le => Opcode::F32Le,
+        Instruction::F32Ge => Opcode::F32Ge,
+        Instruction::F64Eq => Opcode::F64Eq,
+        Instruction::F64Ne => Opcode::F64Ne,
+        Instruction::F64Lt => Opcode::F64Lt,
+        Instruction::F64Gt => Opcode::F64Gt,
+        Instruction::F64Le => Opcode::F64Le,
+        Instruction::F64Ge => Opcode::F64Ge,
+        Instruction::I32Clz => Opcode::I32Clz,
+        Instruction::I32Ctz => Opcode::I32Ctz,
+        Instruction::I32Popcnt => Opcode::I32Popcnt,
+        Instruction::I32Add => Opcode::I32Add,
+        Instruction::I32Sub => Opcode::I32Sub,
+        Instruction::I32Mul => Opcode::I32Mul,
+        Instruction::I32DivS => Opcode::I32DivS,
+        Instruction::I32DivU => Opcode::I32DivU,
+        Instruction::I32RemS => Opcode::I32RemS,
+        Instruction::I32RemU => Opcode::I32RemU,
+        Instruction::I32And => Opcode::I32And,
+        Instruction::I32Or => Opcode::I32Or,
+        Instruction::I32Xor => Opcode::I32Xor,
+        Instruction::I32Shl => Opcode::I32Shl,
+        Instruction::I32ShrS => Opcode::I32ShrS,
+        Instruction::I32ShrU => Opcode::I32ShrU,
+        Instruction::I32Rotl => Opcode::I32Rotl,
+        Instruction::I32Rotr => Opcode::I32Rotr,
+        Instruction::I64Clz => Opcode::I64Clz,
+        Instruction::I64Ctz => Opcode::I64Ctz,
+        Instruction::I64Popcnt => Opcode::I64Popcnt,
+        Instruction::I64Add => Opcode::I64Add,
+        Instruction::I64Sub => Opcode::I64Sub,
+        Instruction::I64Mul => Opcode::I64Mul,
+        Instruction::I64DivS => Opcode::I64DivS,
+        Instruction::I64DivU => Opcode::I64DivU,
+        Instruction::I64RemS => Opcode::I64RemS,
+        Instruction::I64RemU => Opcode::I64RemU,
+        Instruction::I64And => Opcode::I64And,
+        Instruction::I64Or => Opcode::I64Or,
+        Instruction::I64Xor => Opcode::I64Xor,
+        Instruction::I64Shl => Opcode::I64Shl,
+        Instruction::I64ShrS => Opcode::I64ShrS,
+        Instruction::I64ShrU => Opcode::I64ShrU,
+        Instruction::I64Rotl => Opcode::I64Rotl,
+        Instruction::I64Rotr => Opcode::I64Rotr,
+        Instruction::F32Abs => Opcode::F32Abs,
+        Instruction::F32Neg => Opcode::F32Neg,
+        Instruction::F32Ceil => Opcode::F32Ceil,
+        Instruction::F32Floor => Opcode::F32Floor,
+        Instruction::F32Trunc => Opcode::F32Trunc,
+        Instruction::F32Nearest => Opcode::F32Nearest,
+        Instruction::F32Sqrt => Opcode::F32Sqrt,
+        Instruction::F32Add => Opcode::F32Add,
+        Instruction::F32Sub => Opcode::F32Sub,
+        Instruction::F32Mul => Opcode::F32Mul,
+        Instruction::F32Div => Opcode::F32Div,
+        Instruction::F64Abs => Opcode::F64Abs,
+        Instruction::F64Neg => Opcode::F64Neg,
+        Instruction::F64Ceil => Opcode::F64Ceil,
+        Instruction::F64Floor => Opcode::F64Floor,
+        Instruction::F64Trunc => Opcode::F64Trunc,
+        Instruction::F64Nearest => Opcode::F64Nearest,
+        Instruction::F64Sqrt => Opcode::F64Sqrt,
+        Instruction::F64Add => Opcode::F64Add,
+        Instruction::F64Sub => Opcode::F64Sub,
+        Instruction::F64Mul => Opcode::F64Mul,
+        Instruction::F64Div => Opcode::F64Div,
+        Instruction::I32WrapI64 => Opcode::I32WrapI64,
+        Instruction::I32TruncF32S => Opcode::I32TruncF32S,
+        Instruction::I32TruncF32U => Opcode::I32TruncF32U,
+        Instruction::I32TruncF64S => Opcode::I32TruncF64S,
+        Instruction::I32TruncF64U => Opcode::I32TruncF64U,
+        Instruction::I64ExtendI32S => Opcode::I64ExtendI32S,
+        Instruction::I64ExtendI32U => Opcode::I64ExtendI32U,
+        Instruction::I64TruncF32S => Opcode::I64TruncF32S,
+        Instruction::I64TruncF32U => Opcode::I64TruncF32U,
+        Instruction::I64TruncF64S => Opcode::I64TruncF64S,
+        Instruction::I64TruncF64U => Opcode::I64TruncF64U,
+        Instruction::F32ConvertI32S => Opcode::F32ConvertI32S,
+        Instruction::F32ConvertI32U => Opcode::F32ConvertI32U,
+        Instruction::F32ConvertI64S => Opcode::F32ConvertI64S,
+        Instruction::F32ConvertI64U => Opcode::F32ConvertI64U,
+        Instruction::F32DemoteF64 => Opcode::F32DemoteF64,
+        Instruction::F64ConvertI32S => Opcode::F64ConvertI32S,
+        Instruction::F64ConvertI32U => Opcode::F64ConvertI32U,
+        Instruction::F64ConvertI64S => Opcode::F64ConvertI64S,
+        Instruction::F64ConvertI64U => Opcode::F64ConvertI64U,
+        Instruction::F64PromoteF32 => Opcode::F64PromoteF32,
+        Instruction::I32ReinterpretF32 => Opcode::I32ReinterpretF32,
+        Instruction::I64ReinterpretF64 => Opcode::I64ReinterpretF64,
+        Instruction::F32ReinterpretI32 => Opcode::F32ReinterpretI32,
+        Instruction::F64ReinterpretI64 => Opcode::F64ReinterpretI64,
+        Instruction::I32Extend8S => Opcode::I32Extend8S,
+        Instruction::I32Extend16S => Opcode::I32Extend16S,
+        Instruction::I64Extend8S => Opcode::I64Extend8S,
+        Instruction::I64Extend16S => Opcode::I64Extend16S,
+        Instruction::I64Extend32S => Opcode::I64Extend32S,
+        Instruction::RefNull(ref_type) => {
+            buf.push(Opcode::RefNull as u8);
+            return encode_value_type(buf, ref_type);
+        }
+        Instruction::RefIsNull => Opcode::RefIsNull,
+        Instruction::RefFunc(idx) => return encode_op_u32(buf, Opcode::RefFunc, *idx),
+        Instruction::I32TruncSatF32S => return encode_misc(buf, MiscOpcode::I32TruncSatF32S),
+        Instruction::I32TruncSatF32U => return encode_misc(buf, MiscOpcode::I32TruncSatF32U),
+        Instruction::I32TruncSatF64S => return encode_misc(buf, MiscOpcode::I32TruncSatF64S),
+        Instruction::I32TruncSatF64U => return encode_misc(buf, MiscOpcode::I32TruncSatF64U),
+        Instruction::I64TruncSatF32S => return encode_misc(buf, MiscOpcode::I64TruncSatF32S),
+        Instruction::I64TruncSatF32U => return encode_misc(buf, MiscOpcode::I64TruncSatF32U),
+        Instruction::I64TruncSatF64S => return encode_misc(buf, MiscOpcode::I64TruncSatF64S),
+        Instruction::I64TruncSatF64U => return encode_misc(buf, MiscOpcode::I64TruncSatF64U),
+        Instruction::MemoryInit(idx) => {
+            encode_misc(buf, MiscOpcode::MemoryInit);
+            encode_u32(buf, *idx);
+            return buf.push(0x00);
+        }
+        Instruction::DataDrop(idx) => {
+            encode_misc(buf, MiscOpcode::DataDrop);
+            return encode_u32(buf, *idx);
+        }
+        Instruction::MemoryCopy => {
+            encode_misc(buf, MiscOpcode::MemoryCopy);
+            return buf.extend([0x00, 0x00]);
+        }
+        Instruction::MemoryFill => {
+            encode_misc(buf, MiscOpcode::MemoryFill);
+            return buf.push(0x00);
+        }
+    };
+    buf.push(opcode as u8);
+}
+
+// 0xFCに続けて命令番号を書き込む
+fn encode_misc(buf: &mut Vec<u8>, code: MiscOpcode) {
+    buf.push(Opcode::MiscPrefix as u8);
+    encode_u32(buf, code as u32);
+}
+
+fn encode_op_u32(buf: &mut Vec<u8>, opcode: Opcode, value: u32) {
+    buf.push(opcode as u8);
+    encode_u32(buf, value);
+}
+
+// メモリアクセス命令の即値(アライメントとオフセット)
+fn encode_memarg(buf: &mut Vec<u8>, opcode: Opcode, align: u32, offset: u32) {
+    buf.push(opcode as u8);
+    encode_u32(buf, align);
+    encode_u32(buf, offset);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode_i64, encode_u32};
+    #[cfg(feature = "std")]
+    use crate::binary::module::Module;
+    use alloc::{vec, vec::Vec};
+    #[cfg(feature = "std")]
+    use anyhow::Result;
+
+    #[test]
+    fn encode_leb128() {
+        let tests: Vec<(u32, Vec<u8>)> = vec![
+            (0, vec![0x00]),
+            (127, vec![0x7f]),
+            (128, vec![0x80, 0x01]),
+            (624485, vec![0xe5, 0x8e, 0x26]),
+        ];
+        for (value, want) in tests {
+            let mut buf = vec![];
+            encode_u32(&mut buf, value);
+            assert_eq!(buf, want, "{value}");
+        }
+
+        let tests: Vec<(i64, Vec<u8>)> = vec![
+            (0, vec![0x00]),
+            (63, vec![0x3f]),
+            (64, vec![0xc0, 0x00]),
+            (-1, vec![0x7f]),
+            (-64, vec![0x40]),
+            (-65, vec![0xbf, 0x7f]),
+            (-123456, vec![0xc0, 0xbb, 0x78]),
+        ];
+        for (value, want) in tests {
+            let mut buf = vec![];
+            encode_i64(&mut buf, value);
+            assert_eq!(buf, want, "{value}");
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn encode_round_trip() -> Result<()> {
+        // fixturesのすべてのモジュールで、エンコードしてデコードし直すと元に戻る
+        for entry in std::fs::read_dir("src/fixtures")? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "wat") {
+                continue;
+            }
+            let module = Module::from_file(&path)?;
+            let decoded = Module::new(&module.encode())?;
+            assert_eq!(decoded, module, "{}", path.display());
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn encode_same_bytes() -> Result<()> {
+        // 名前を使わないモジュールはwatが出力したバイト列と一致する
+        let wasm = std::fs::read("src/fixtures/func_add.wasm")?;
+        assert_eq!(Module::new(&wasm)?.encode(), wasm);
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+use core::fmt;
+use nom::error::{ContextError, ErrorKind, ParseError};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    Nom(ErrorKind),          // nomのパーサでのエラー
+    UnknownOpcode(u8),       // 未知または未実装のオペコード
+    UnknownMiscOpcode(u32),  // 0xFCに続く未知または未実装の命令番号
+    UnknownSectionCode(u8),  // 未知のセクションID
+    DuplicateSection(u8),    // 同じセクションが2回以上現れた
+    SectionOutOfOrder(u8),   // セクションの順序が仕様と異なる
+    InvalidFuncType(u8),     // 関数シグネチャが0x60で始まっていない
+    UnsupportedVersion(u32), // 未対応のバージョン
+    InvalidValueType(u8),    // 未知の値の型
+    InvalidElemType(u8),     // テーブルの要素の型がfuncrefでない
+    InvalidImportKind(u8),   // 未知のインポートの種類
+    InvalidUtf8,             // 名前がUTF-8として不正
+    InvalidExportKind(u8),   // 未知のエクスポートの種類
+    InvalidDataFlags(u32),   // 未知のデータセグメントの種類
+    InvalidConstExpr(u8),    // 初期化式に使えない命令
+    InvalidElemFlags(u32),   // 未知の要素セグメントの種類
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::Nom(kind) => write!(f, "{}", kind.description()),
+            DecodeError::UnknownOpcode(byte) => {
+                write!(f, "invalid or unimplemented opcode: {:#04x}", byte)
+            }
+            DecodeError::UnknownMiscOpcode(code) => {
+                write!(f, "invalid or unimplemented opcode: 0xfc {}", code)
+            }
+            DecodeError::UnknownSectionCode(code) => {
+                write!(f, "unknown section code: {:#04x}", code)
+            }
+            DecodeError::DuplicateSection(code) => {
+                write!(f, "duplicate section: {:#04x}", code)
+            }
+            DecodeError::SectionOutOfOrder(code) => {
+                write!(f, "section out of order: {:#04x}", code)
+            }
+            DecodeError::InvalidFuncType(byte) => {
+                write!(f, "invalid function type form: {:#04x}", byte)
+            }
+            DecodeError::UnsupportedVersion(version) => {
+                write!(f, "unsupported wasm version: {}", version)
+            }
+            DecodeError::InvalidValueType(byte) => write!(f, "invalid value type: {:#04x}", byte),
+            DecodeError::InvalidElemType(byte) => write!(f, "invalid elem type: {:#04x}", byte),
+            DecodeError::InvalidImportKind(byte) => {
+                write!(f, "invalid import kind: {:#04x}", byte)
+            }
+            DecodeError::InvalidUtf8 => write!(f, "malformed UTF-8 encoding"),
+            DecodeError::InvalidExportKind(byte) => {
+                write!(f, "invalid export kind: {:#04x}", byte)
+            }
+            DecodeError::InvalidDataFlags(flags) => {
+                write!(f, "invalid data segment flags: {:#04x}", flags)
+            }
+            DecodeError::InvalidConstExpr(byte) => {
+                write!(f, "constant expression required: {:#04x}", byte)
+            }
+            DecodeError::InvalidElemFlags(flags) => {
+                write!(f, "invalid elem segment flags: {:#04x}", flags)
+            }
+        }
+    }
+}
+
+impl core::error::Error for DecodeError {}
+
+impl<I> ParseError<I> for DecodeError {
+    fn from_error_kind(_: I, kind: ErrorKind) -> Self {
+        DecodeError::Nom(kind)
+    }
+
+    fn append(_: I, _: ErrorKind, other: Self) -> Self {
+        other
+    }
+}
+
+impl<I> ContextError<I> for DecodeError {}
+
+// デコード処理で使うIResult
+pub type IResult<I, O> = nom::IResult<I, O, DecodeError>;
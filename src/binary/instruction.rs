@@ -1,8 +1,203 @@
+use super::types::{BlockType, ValueType};
+use alloc::{boxed::Box, vec::Vec};
+
+// br_tableの分岐先(Instructionを小さく保つためBoxに入れる)
 #[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrTable {
+    pub targets: Vec<u32>,
+    pub default: u32, // 範囲外のときの分岐先
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum Instruction {
+    Unreachable,
+    Nop,
+    Block(BlockType),
+    Loop(BlockType),
+    If(BlockType),
+    Else,
     End,
+    Br(u32),
+    BrIf(u32),
+    BrTable(Box<BrTable>),
+    Call(u32),
+    CallIndirect { type_idx: u32, table_idx: u32 },
     LocalGet(u32),
+    LocalSet(u32),
+    LocalTee(u32),
+    GlobalGet(u32),
+    GlobalSet(u32),
+    TableGet(u32), // テーブルのインデックス
+    TableSet(u32),
+    I32Load { align: u32, offset: u32 },
+    I64Load { align: u32, offset: u32 },
+    I32Load8S { align: u32, offset: u32 },
+    I32Load8U { align: u32, offset: u32 },
+    I32Load16S { align: u32, offset: u32 },
+    I32Load16U { align: u32, offset: u32 },
+    I64Load8S { align: u32, offset: u32 },
+    I64Load8U { align: u32, offset: u32 },
+    I64Load16S { align: u32, offset: u32 },
+    I64Load16U { align: u32, offset: u32 },
+    I64Load32S { align: u32, offset: u32 },
+    I64Load32U { align: u32, offset: u32 },
+    I32Store { align: u32, offset: u32 },
+    I64Store { align: u32, offset: u32 },
+    I32Store8 { align: u32, offset: u32 },
+    I32Store16 { align: u32, offset: u32 },
+    I64Store8 { align: u32, offset: u32 },
+    I64Store16 { align: u32, offset: u32 },
+    I64Store32 { align: u32, offset: u32 },
+    I32Const(i32),
     I64Const(i64),
+    F32Const(f32),
+    F64Const(f64),
+    I32Eqz,
+    I32Eq,
+    I32Ne,
+    I32LtS,
+    I32LtU,
+    I32GtS,
+    I32GtU,
+    I32LeS,
+    I32LeU,
+    I32GeS,
+    I32GeU,
+    I64Eqz,
+    I64Eq,
+    I64Ne,
+    I64LtS,
+    I64LtU,
+    I64GtS,
+    I64GtU,
+    I64LeS,
+    I64LeU,
+    I64GeS,
+    I64GeU,
+    F32Eq,
+    F32Ne,
+    F32Lt,
+    F32Gt,
+    F32Le,
+    F32Ge,
+    F64Eq,
+    F64Ne,
+    F64Lt,
+    F64Gt,
+    F64Le,
+    F64Ge,
+    I32Clz,
+    I32Ctz,
+    I32Popcnt,
     I32Add,
+    I32Sub,
+    I32Mul,
+    I32DivS,
+    I32DivU,
+    I32RemS,
+    I32RemU,
+    I32And,
+    I32Or,
+    I32Xor,
+    I32Shl,
+    I32ShrS,
+    I32ShrU,
+    I32Rotl,
+    I32Rotr,
+    I64Clz,
+    I64Ctz,
+    I64Popcnt,
     I64Add,
+    I64Sub,
+    I64Mul,
+    I64DivS,
+    I64DivU,
+    I64RemS,
+    I64RemU,
+    I64And,
+    I64Or,
+    I64Xor,
+    I64Shl,
+    I64ShrS,
+    I64ShrU,
+    I64Rotl,
+    I64Rotr,
+    F32Abs,
+    F32Neg,
+    F32Ceil,
+    F32Floor,
+    F32Trunc,
+    F32Nearest,
+    F32Sqrt,
+    F32Add,
+    F32Sub,
+    F32Mul,
+    F32Div,
+    F64Abs,
+    F64Neg,
+    F64Ceil,
+    F64Floor,
+    F64Trunc,
+    F64Nearest,
+    F64Sqrt,
+    F64Add,
+    F64Sub,
+    F64Mul,
+    F64Div,
+    I32WrapI64,
+    I32TruncF32S,
+    I32TruncF32U,
+    I32TruncF64S,
+    I32TruncF64U,
+    I64ExtendI32S,
+    I64ExtendI32U,
+    I64TruncF32S,
+    I64TruncF32U,
+    I64TruncF64S,
+    I64TruncF64U,
+    F32ConvertI32S,
+    F32ConvertI32U,
+    F32ConvertI64S,
+    F32ConvertI64U,
+    F32DemoteF64,
+    F64ConvertI32S,
+    F64ConvertI32U,
+    F64ConvertI64S,
+    F64ConvertI64U,
+    F64PromoteF32,
+    I32ReinterpretF32,
+    I64ReinterpretF64,
+    F32ReinterpretI32,
+    F64ReinterpretI64,
+    I32Extend8S,
+    I32Extend16S,
+    I64Extend8S,
+    I64Extend16S,
+    I64Extend32S,
+    RefNull(ValueType), // 参照の型(funcrefのみ)
+    RefIsNull,
+    RefFunc(u32), // 関数のインデックス
+    I32TruncSatF32S,
+    I32TruncSatF32U,
+    I32TruncSatF64S,
+    I32TruncSatF64U,
+    I64TruncSatF32S,
+    I64TruncSatF32U,
+    I64TruncSatF64S,
+    I64TruncSatF64U,
+    MemoryInit(u32), // データセグメントのインデックス
+    DataDrop(u32),
+    MemoryCopy,
+    MemoryFill,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Instruction;
+
+    #[test]
+    fn instruction_size() {
+        // 実行ループでキャッシュに乗りやすいよう、2ワードに収める
+        assert!(core::mem::size_of::<Instruction>() <= 16);
+    }
 }
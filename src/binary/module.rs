@@ -1,26 +1,48 @@
 use super::{
-    instruction::Instruction,
-    opcode::Opcode,
+    error::{DecodeError, IResult},
+    instruction::{BrTable, Instruction},
+    opcode::{MiscOpcode, Opcode},
     section::{Function, SectionCode},
-    types::{Export, ExportDesc, FuncType, FunctionLocal, ValueType},
+    types::{
+        BlockType, ConstExpr, Data, DataMode, ElemType, Element, ElementMode, Export, ExportDesc,
+        FuncType, FunctionLocal, Global, GlobalType, Import, ImportDesc, Limits, Memory, Table,
+        ValueType,
+    },
+};
+use alloc::{
+    boxed::Box,
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
 };
 use nom::{
     bytes::complete::{tag, take},
     multi::many0,
-    number::complete::{le_u32, le_u8},
-    IResult,
+    number::complete::{le_u32, le_u64, le_u8},
 };
-use nom_leb128::{leb128_i64, leb128_u32};
+use nom_leb128::{leb128_i32, leb128_i64, leb128_u32};
 use num_traits::FromPrimitive as _;
+#[cfg(feature = "std")]
+use std::{io::Read, path::Path};
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq)]
 pub struct Module {
     pub magic: String,
     pub version: u32,
     pub type_section: Option<Vec<FuncType>>,
+    pub import_section: Option<Vec<Import>>,
     pub function_section: Option<Vec<u32>>,
     pub code_section: Option<Vec<Function>>,
+    pub table_section: Option<Vec<Table>>,
+    pub memory_section: Option<Vec<Memory>>,
+    pub global_section: Option<Vec<Global>>,
     pub export_section: Option<Vec<Export>>,
+    pub start_section: Option<u32>,
+    pub element_section: Option<Vec<Element>>,
+    pub data_count_section: Option<u32>,
+    pub data_section: Option<Vec<Data>>,
+    pub name_section: Option<BTreeMap<u32, String>>, // 関数インデックスと関数名
 }
 
 impl Default for Module {
@@ -29,63 +51,279 @@ impl Default for Module {
             magic: "\0asm".to_string(),
             version: 1,
             type_section: None,
+            import_section: None,
             function_section: None,
             code_section: None,
+            table_section: None,
+            memory_section: None,
+            global_section: None,
             export_section: None,
+            start_section: None,
+            element_section: None,
+            data_count_section: None,
+            data_section: None,
+            name_section: None,
         }
     }
 }
 
+// モジュールの規模を表す統計情報
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ModuleStats {
+    pub types: usize,        // 関数シグネチャの数
+    pub imports: usize,      // インポートの数
+    pub functions: usize,    // モジュール内で定義した関数の数
+    pub exports: usize,      // エクスポートの数
+    pub memory_pages: u32,   // 線形メモリの初期ページ数
+    pub instructions: usize, // 全関数本体の命令数(endを含む)
+}
+
 impl Module {
     pub fn new(input: &[u8]) -> anyhow::Result<Module> {
-        let (_, module) =
-            Module::decode(input).map_err(|e| anyhow::anyhow!("failed to parse wasm: {}", e))?;
+        let (_, module) = Module::decode(input).map_err(parse_error)?;
         Ok(module)
     }
 
+    // 全体をメモリに読み込まず、セクションごとに読み取ってデコードする
+    #[cfg(feature = "std")]
+    pub fn decode_reader<R: Read>(mut reader: R) -> anyhow::Result<Module> {
+        let mut header = [0; 8];
+        reader.read_exact(&mut header)?;
+        let (_, mut module) = decode_header(&header).map_err(parse_error)?;
+
+        let mut last_order = 0;
+        while let Some(code) = read_byte(&mut reader)? {
+            let Some(code) = SectionCode::from_u8(code) else {
+                return Err(parse_error(nom::Err::Failure(
+                    DecodeError::UnknownSectionCode(code),
+                )));
+            };
+            check_section_order(code, &mut last_order).map_err(parse_error)?;
+
+            // サイズは信用できないので、先に領域を確保せずに読み取る
+            let size = read_leb128_u32(&mut reader)?;
+            let mut contents = vec![];
+            (&mut reader).take(size as u64).read_to_end(&mut contents)?;
+            if contents.len() != size as usize {
+                anyhow::bail!("failed to parse wasm: incomplete input");
+            }
+            module
+                .decode_section(code, &contents)
+                .map_err(parse_error)?;
+        }
+        Ok(module)
+    }
+
+    // .watはテキスト形式として、それ以外はバイナリ形式として読み込む
+    #[cfg(feature = "std")]
+    pub fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Module> {
+        let wasm = read_wasm_file(path)?;
+        Module::new(&wasm)
+    }
+
+    // 存在しないセクションは0として数える
+    pub fn stats(&self) -> ModuleStats {
+        ModuleStats {
+            types: self.type_section.as_ref().map_or(0, Vec::len),
+            imports: self.import_section.as_ref().map_or(0, Vec::len),
+            functions: self.code_section.as_ref().map_or(0, Vec::len),
+            exports: self.export_section.as_ref().map_or(0, Vec::len),
+            memory_pages: self
+                .memory_section
+                .iter()
+                .flatten()
+                .map(|memory| memory.limits.min)
+                .sum(),
+            instructions: self
+                .code_section
+                .iter()
+                .flatten()
+                .map(|func| func.code.len())
+                .sum(),
+        }
+    }
+
+    // 関数インデックス(インポートした関数を含む)の関数本体の命令列
+    pub fn instructions(&self, func_idx: usize) -> Option<&[Instruction]> {
+        let imported = self
+            .import_section
+            .iter()
+            .flatten()
+            .filter(|import| matches!(import.desc, ImportDesc::Func(_)))
+            .count();
+        // インポートした関数は本体を持たない
+        let idx = func_idx.checked_sub(imported)?;
+        let func = self.code_section.as_ref()?.get(idx)?;
+        Some(&func.code)
+    }
+
     fn decode(input: &[u8]) -> IResult<&[u8], Module> {
-        let (input, _) = tag(b"\0asm")(input)?;
-        let (input, version) = le_u32(input)?;
-        let mut module = Module {
-            magic: "\0asm".to_string(),
-            version,
-            ..Default::default()
-        };
+        let (input, mut module) = decode_header(input)?;
 
         let mut remaining = input;
+        let mut last_order = 0;
         while !remaining.is_empty() {
-            match decode_section_header(remaining) {
-                Ok((input, (code, size))) => {
-                    println!("[+] (1) 各Sectionのサイズ: {:?}", size); //(1)
-
-                    // 指定したサイズ分だけ読み取る
-                    let (rest, section_contents) = take(size)(input)?;
-
-                    match code {
-                        SectionCode::Type => {
-                            let (_, types) = decode_type_section(section_contents)?;
-                            module.type_section = Some(types);
-                        }
-                        SectionCode::Function => {
-                            let (_, func_idx_list) = decode_function_section(section_contents)?;
-                            module.function_section = Some(func_idx_list);
-                        }
-                        SectionCode::Code => {
-                            let (_, funcs) = decode_code_section(section_contents)?;
-                            module.code_section = Some(funcs);
-                        }
-                        SectionCode::Export => {
-                            let (_, exports) = decode_export_section(section_contents)?;
-                            module.export_section = Some(exports);
-                        }
-                        _ => todo!(),
-                    };
-                    remaining = rest;
+            let (input, (code, size)) = decode_section_header(remaining)?;
+            check_section_order(code, &mut last_order)?;
+            // 指定したサイズ分だけ読み取る
+            let (rest, contents) = take(size)(input)?;
+            module.decode_section(code, contents)?;
+            remaining = rest;
+        }
+        Ok((input, module))
+    }
+
+    // セクションの中身を読み取り、対応するフィールドに格納する
+    fn decode_section<'a>(
+        &mut self,
+        code: SectionCode,
+        contents: &'a [u8],
+    ) -> IResult<&'a [u8], ()> {
+        match code {
+            SectionCode::Type => {
+                let (_, types) = decode_type_section(contents)?;
+                self.type_section = Some(types);
+            }
+            SectionCode::Import => {
+                let (_, imports) = decode_import_section(contents)?;
+                self.import_section = Some(imports);
+            }
+            SectionCode::Function => {
+                let (_, func_idx_list) = decode_function_section(contents)?;
+                self.function_section = Some(func_idx_list);
+            }
+            SectionCode::Code => {
+                let (_, funcs) = decode_code_section(contents)?;
+                self.code_section = Some(funcs);
+            }
+            SectionCode::Table => {
+                let (_, tables) = decode_table_section(contents)?;
+                self.table_section = Some(tables);
+            }
+            SectionCode::Memory => {
+                let (_, memories) = decode_memory_section(contents)?;
+                self.memory_section = Some(memories);
+            }
+            SectionCode::Global => {
+                let (_, globals) = decode_global_section(contents)?;
+                self.global_section = Some(globals);
+            }
+            SectionCode::Export => {
+                let (_, exports) = decode_export_section(contents)?;
+                self.export_section = Some(exports);
+            }
+            SectionCode::Start => {
+                let (_, func_idx) = leb128_u32(contents)?;
+                self.start_section = Some(func_idx);
+            }
+            SectionCode::Element => {
+                let (_, elements) = decode_element_section(contents)?;
+                self.element_section = Some(elements);
+            }
+            SectionCode::DataCount => {
+                let (_, count) = leb128_u32(contents)?;
+                self.data_count_section = Some(count);
+            }
+            SectionCode::Data => {
+                let (_, datas) = decode_data_section(contents)?;
+                self.data_section = Some(datas);
+            }
+            // Custom Sectionは名前("name")だけ読み、それ以外は読み飛ばす
+            SectionCode::Custom => {
+                let (rest, name) = decode_name(contents)?;
+                if name == "name" {
+                    let (_, names) = decode_name_section(rest)?;
+                    self.name_section = Some(names);
                 }
-                Err(err) => return Err(err),
             }
+        };
+        Ok((&[], ()))
+    }
+}
+
+fn parse_error(e: nom::Err<DecodeError>) -> anyhow::Error {
+    match e {
+        nom::Err::Error(e) | nom::Err::Failure(e) => {
+            anyhow::anyhow!("failed to parse wasm: {}", e)
         }
-        Ok((input, module))
+        nom::Err::Incomplete(_) => anyhow::anyhow!("failed to parse wasm: incomplete input"),
+    }
+}
+
+// 終端に達した場合はNoneを返す
+#[cfg(feature = "std")]
+fn read_byte(reader: &mut impl Read) -> std::io::Result<Option<u8>> {
+    let mut byte = [0];
+    loop {
+        match reader.read(&mut byte) {
+            Ok(0) => return Ok(None),
+            Ok(_) => return Ok(Some(byte[0])),
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+// LEB128のu32は最大5バイト
+#[cfg(feature = "std")]
+fn read_leb128_u32(reader: &mut impl Read) -> anyhow::Result<u32> {
+    let mut bytes = vec![];
+    loop {
+        let Some(byte) = read_byte(reader)? else {
+            anyhow::bail!("failed to parse wasm: incomplete input");
+        };
+        bytes.push(byte);
+        if byte & 0x80 == 0 || bytes.len() == 5 {
+            break;
+        }
+    }
+    let (_, value) = leb128_u32::<_, DecodeError>(bytes.as_slice()).map_err(parse_error)?;
+    Ok(value)
+}
+
+// マジックナンバーとバージョンを読み取る
+fn decode_header(input: &[u8]) -> IResult<&[u8], Module> {
+    let (input, _) = tag(b"\0asm")(input)?;
+    let (input, version) = le_u32(input)?;
+    if version != 1 {
+        return Err(nom::Err::Failure(DecodeError::UnsupportedVersion(version)));
+    }
+    let module = Module {
+        magic: "\0asm".to_string(),
+        version,
+        ..Default::default()
+    };
+    Ok((input, module))
+}
+
+// Custom Section以外は決められた順に高々1回だけ現れる
+fn check_section_order(
+    code: SectionCode,
+    last_order: &mut u8,
+) -> Result<(), nom::Err<DecodeError>> {
+    let order = code.order();
+    if order == 0 {
+        return Ok(());
+    }
+    let code = code as u8;
+    if order == *last_order {
+        return Err(nom::Err::Failure(DecodeError::DuplicateSection(code)));
+    }
+    if order < *last_order {
+        return Err(nom::Err::Failure(DecodeError::SectionOutOfOrder(code)));
+    }
+    *last_order = order;
+    Ok(())
+}
+
+// ファイルを読み込み、.watの場合はバイナリ形式に変換する
+#[cfg(feature = "std")]
+pub fn read_wasm_file(path: impl AsRef<Path>) -> anyhow::Result<Vec<u8>> {
+    let path = path.as_ref();
+    if path.extension().is_some_and(|ext| ext == "wat") {
+        Ok(wat::parse_file(path)?)
+    } else {
+        Ok(std::fs::read(path)?)
     }
 }
 
@@ -93,18 +331,22 @@ fn decode_section_header(input: &[u8]) -> IResult<&[u8], (SectionCode, u32)> {
     let (input, code) = le_u8(input)?;
     let (input, size) = leb128_u32(input)?;
 
-    Ok((
-        input,
-        (
-            SectionCode::from_u8(code).expect("unexpected section code"),
-            size,
-        ),
-    ))
+    let Some(code) = SectionCode::from_u8(code) else {
+        return Err(nom::Err::Failure(DecodeError::UnknownSectionCode(code)));
+    };
+    Ok((input, (code, size)))
 }
 
 fn decode_value_type(input: &[u8]) -> IResult<&[u8], ValueType> {
     let (input, value_type) = le_u8(input)?;
-    Ok((input, value_type.into()))
+    let value_type = ValueType::try_from(value_type).map_err(nom::Err::Failure)?;
+    Ok((input, value_type))
+}
+
+fn decode_elem_type(input: &[u8]) -> IResult<&[u8], ElemType> {
+    let (input, elem_type) = le_u8(input)?;
+    let elem_type = ElemType::try_from(elem_type).map_err(nom::Err::Failure)?;
+    Ok((input, elem_type))
 }
 
 fn decode_type_section(input: &[u8]) -> IResult<&[u8], Vec<FuncType>> {
@@ -113,7 +355,11 @@ fn decode_type_section(input: &[u8]) -> IResult<&[u8], Vec<FuncType>> {
 
     // 関数シグネチャの個数分、読み取る
     for _ in 0..count {
-        let (rest, _) = le_u8(input)?; // 関数シグネチャの種類を表す値(0x60)
+        // 関数シグネチャの種類を表す値(0x60)
+        let (rest, form) = le_u8(input)?;
+        if form != 0x60 {
+            return Err(nom::Err::Failure(DecodeError::InvalidFuncType(form)));
+        }
         let mut func = FuncType::default();
 
         // 引数の個数を読み取る
@@ -137,6 +383,90 @@ fn decode_type_section(input: &[u8]) -> IResult<&[u8], Vec<FuncType>> {
     Ok((&[], func_types))
 }
 
+fn decode_import_section(input: &[u8]) -> IResult<&[u8], Vec<Import>> {
+    let (mut input, count) = leb128_u32(input)?;
+    let mut imports = vec![];
+
+    for _ in 0..count {
+        // モジュール名とインポートする名前
+        let (rest, module) = decode_name(input)?;
+        let (rest, field) = decode_name(rest)?;
+
+        // インポートの種類
+        let (rest, import_kind) = le_u8(rest)?;
+        let (rest, desc) = match import_kind {
+            0x00 => {
+                let (rest, idx) = leb128_u32(rest)?;
+                (rest, ImportDesc::Func(idx))
+            }
+            0x01 => {
+                let (rest, elem_type) = decode_elem_type(rest)?;
+                let (rest, limits) = decode_limits(rest)?;
+                let table = Table { elem_type, limits };
+                (rest, ImportDesc::Table(table))
+            }
+            0x02 => {
+                let (rest, limits) = decode_limits(rest)?;
+                (rest, ImportDesc::Memory(Memory { limits }))
+            }
+            0x03 => {
+                let (rest, value_type) = decode_value_type(rest)?;
+                let (rest, mutability) = le_u8(rest)?;
+                let global_type = GlobalType {
+                    value_type,
+                    mutable: mutability == 0x01,
+                };
+                (rest, ImportDesc::Global(global_type))
+            }
+            _ => {
+                return Err(nom::Err::Failure(DecodeError::InvalidImportKind(
+                    import_kind,
+                )))
+            }
+        };
+
+        imports.push(Import {
+            module,
+            field,
+            desc,
+        });
+        input = rest;
+    }
+    Ok((input, imports))
+}
+
+// バイト列の長さとUTF-8の文字列
+fn decode_name(input: &[u8]) -> IResult<&[u8], String> {
+    let (input, len) = leb128_u32(input)?;
+    let (input, bytes) = take(len)(input)?;
+    let Ok(name) = String::from_utf8(bytes.to_vec()) else {
+        return Err(nom::Err::Failure(DecodeError::InvalidUtf8));
+    };
+    Ok((input, name))
+}
+
+// nameセクションのうち、関数名のサブセクション(id 1)だけを読む
+fn decode_name_section(input: &[u8]) -> IResult<&[u8], BTreeMap<u32, String>> {
+    let mut names = BTreeMap::new();
+    let mut input = input;
+    while !input.is_empty() {
+        let (rest, id) = le_u8(input)?;
+        let (rest, size) = leb128_u32(rest)?;
+        let (rest, contents) = take(size)(rest)?;
+        if id == 1 {
+            let (mut contents, count) = leb128_u32(contents)?;
+            for _ in 0..count {
+                let (rest, idx) = leb128_u32(contents)?;
+                let (rest, name) = decode_name(rest)?;
+                names.insert(idx, name);
+                contents = rest;
+            }
+        }
+        input = rest;
+    }
+    Ok((input, names))
+}
+
 fn decode_function_section(input: &[u8]) -> IResult<&[u8], Vec<u32>> {
     let mut func_idx_list = vec![];
     let (mut input, count) = leb128_u32(input)?;
@@ -156,7 +486,6 @@ fn decode_code_section(input: &[u8]) -> IResult<&[u8], Vec<Function>> {
 
     for _ in 0..count {
         let (rest, size) = leb128_u32(input)?; // func body size
-        println!("[+] (2) 関数のサイズ: {:?}", size); // (2)
         let (rest, body) = take(size)(rest)?;
         let (_, body) = decode_function_body(body)?;
         functions.push(body);
@@ -175,10 +504,10 @@ fn decode_function_body(input: &[u8]) -> IResult<&[u8], Function> {
         // 型の数
         let (rest, type_count) = leb128_u32(input)?;
         // 型
-        let (rest, value_type) = le_u8(rest)?;
+        let (rest, value_type) = decode_value_type(rest)?;
         body.locals.push(FunctionLocal {
             type_count,
-            value_type: value_type.into(),
+            value_type,
         });
         input = rest;
     }
@@ -196,40 +525,596 @@ fn decode_function_body(input: &[u8]) -> IResult<&[u8], Function> {
 
 fn decode_instructions(input: &[u8]) -> IResult<&[u8], Instruction> {
     let (input, byte) = le_u8(input)?;
-    let op = Opcode::from_u8(byte)
-        .unwrap_or_else(|| panic!("invalid or unimplemented opcode: {:X}", byte));
+    let Some(op) = Opcode::from_u8(byte) else {
+        return Err(nom::Err::Failure(DecodeError::UnknownOpcode(byte)));
+    };
 
     let (rest, inst) = match op {
+        Opcode::Unreachable => (input, Instruction::Unreachable),
+        Opcode::Nop => (input, Instruction::Nop),
+        Opcode::Block => {
+            let (rest, block_type) = decode_block_type(input)?;
+            (rest, Instruction::Block(block_type))
+        }
+        Opcode::Loop => {
+            let (rest, block_type) = decode_block_type(input)?;
+            (rest, Instruction::Loop(block_type))
+        }
+        Opcode::If => {
+            let (rest, block_type) = decode_block_type(input)?;
+            (rest, Instruction::If(block_type))
+        }
+        Opcode::Else => (input, Instruction::Else),
         Opcode::End => (input, Instruction::End),
+        Opcode::Br => {
+            let (rest, depth) = leb128_u32(input)?;
+            (rest, Instruction::Br(depth))
+        }
+        Opcode::BrIf => {
+            let (rest, depth) = leb128_u32(input)?;
+            (rest, Instruction::BrIf(depth))
+        }
+        Opcode::BrTable => {
+            let (mut rest, count) = leb128_u32(input)?;
+            let mut targets = vec![];
+            for _ in 0..count {
+                let (next, depth) = leb128_u32(rest)?;
+                targets.push(depth);
+                rest = next;
+            }
+            let (rest, default) = leb128_u32(rest)?;
+            (
+                rest,
+                Instruction::BrTable(Box::new(BrTable { targets, default })),
+            )
+        }
+        Opcode::Call => {
+            let (rest, idx) = leb128_u32(input)?;
+            (rest, Instruction::Call(idx))
+        }
+        Opcode::CallIndirect => {
+            let (rest, type_idx) = leb128_u32(input)?;
+            let (rest, table_idx) = leb128_u32(rest)?;
+            (
+                rest,
+                Instruction::CallIndirect {
+                    type_idx,
+                    table_idx,
+                },
+            )
+        }
         Opcode::LocalGet => {
             let (rest, idx) = leb128_u32(input)?;
             (rest, Instruction::LocalGet(idx))
         }
+        Opcode::LocalSet => {
+            let (rest, idx) = leb128_u32(input)?;
+            (rest, Instruction::LocalSet(idx))
+        }
+        Opcode::LocalTee => {
+            let (rest, idx) = leb128_u32(input)?;
+            (rest, Instruction::LocalTee(idx))
+        }
+        Opcode::GlobalGet => {
+            let (rest, idx) = leb128_u32(input)?;
+            (rest, Instruction::GlobalGet(idx))
+        }
+        Opcode::GlobalSet => {
+            let (rest, idx) = leb128_u32(input)?;
+            (rest, Instruction::GlobalSet(idx))
+        }
+        Opcode::TableGet => {
+            let (rest, idx) = leb128_u32(input)?;
+            (rest, Instruction::TableGet(idx))
+        }
+        Opcode::TableSet => {
+            let (rest, idx) = leb128_u32(input)?;
+            (rest, Instruction::TableSet(idx))
+        }
+        Opcode::I32Load => {
+            let (rest, (align, offset)) = decode_memarg(input)?;
+            (rest, Instruction::I32Load { align, offset })
+        }
+        Opcode::I64Load => {
+            let (rest, (align, offset)) = decode_memarg(input)?;
+            (rest, Instruction::I64Load { align, offset })
+        }
+        Opcode::I32Load8S => {
+            let (rest, (align, offset)) = decode_memarg(input)?;
+            (rest, Instruction::I32Load8S { align, offset })
+        }
+        Opcode::I32Load8U => {
+            let (rest, (align, offset)) = decode_memarg(input)?;
+            (rest, Instruction::I32Load8U { align, offset })
+        }
+        Opcode::I32Load16S => {
+            let (rest, (align, offset)) = decode_memarg(input)?;
+            (rest, Instruction::I32Load16S { align, offset })
+        }
+        Opcode::I32Load16U => {
+            let (rest, (align, offset)) = decode_memarg(input)?;
+            (rest, Instruction::I32Load16U { align, offset })
+        }
+        Opcode::I64Load8S => {
+            let (rest, (align, offset)) = decode_memarg(input)?;
+            (rest, Instruction::I64Load8S { align, offset })
+        }
+        Opcode::I64Load8U => {
+            let (rest, (align, offset)) = decode_memarg(input)?;
+            (rest, Instruction::I64Load8U { align, offset })
+        }
+        Opcode::I64Load16S => {
+            let (rest, (align, offset)) = decode_memarg(input)?;
+            (rest, Instruction::I64Load16S { align, offset })
+        }
+        Opcode::I64Load16U => {
+            let (rest, (align, offset)) = decode_memarg(input)?;
+            (rest, Instruction::I64Load16U { align, offset })
+        }
+        Opcode::I64Load32S => {
+            let (rest, (align, offset)) = decode_memarg(input)?;
+            (rest, Instruction::I64Load32S { align, offset })
+        }
+        Opcode::I64Load32U => {
+            let (rest, (align, offset)) = decode_memarg(input)?;
+            (rest, Instruction::I64Load32U { align, offset })
+        }
+        Opcode::I32Store => {
+            let (rest, (align, offset)) = decode_memarg(input)?;
+            (rest, Instruction::I32Store { align, offset })
+        }
+        Opcode::I64Store => {
+            let (rest, (align, offset)) = decode_memarg(input)?;
+            (rest, Instruction::I64Store { align, offset })
+        }
+        Opcode::I32Store8 => {
+            let (rest, (align, offset)) = decode_memarg(input)?;
+            (rest, Instruction::I32Store8 { align, offset })
+        }
+        Opcode::I32Store16 => {
+            let (rest, (align, offset)) = decode_memarg(input)?;
+            (rest, Instruction::I32Store16 { align, offset })
+        }
+        Opcode::I64Store8 => {
+            let (rest, (align, offset)) = decode_memarg(input)?;
+            (rest, Instruction::I64Store8 { align, offset })
+        }
+        Opcode::I64Store16 => {
+            let (rest, (align, offset)) = decode_memarg(input)?;
+            (rest, Instruction::I64Store16 { align, offset })
+        }
+        Opcode::I64Store32 => {
+            let (rest, (align, offset)) = decode_memarg(input)?;
+            (rest, Instruction::I64Store32 { align, offset })
+        }
+        Opcode::I32Const => {
+            let (rest, val) = leb128_i32(input)?;
+            (rest, Instruction::I32Const(val))
+        }
         Opcode::I64Const => {
-            println!("[+] (3) i64.const 検出"); // (3)
-
             let (rest, val) = leb128_i64(input)?;
             (rest, Instruction::I64Const(val))
         }
+        // 浮動小数点数はLEB128ではなくIEEE 754のリトルエンディアン
+        Opcode::F32Const => {
+            let (rest, bits) = le_u32(input)?;
+            (rest, Instruction::F32Const(f32::from_bits(bits)))
+        }
+        Opcode::F64Const => {
+            let (rest, bits) = le_u64(input)?;
+            (rest, Instruction::F64Const(f64::from_bits(bits)))
+        }
+        Opcode::I32Eqz => (input, Instruction::I32Eqz),
+        Opcode::I32Eq => (input, Instruction::I32Eq),
+        Opcode::I32Ne => (input, Instruction::I32Ne),
+        Opcode::I32LtS => (input, Instruction::I32LtS),
+        Opcode::I32LtU => (input, Instruction::I32LtU),
+        Opcode::I32GtS => (input, Instruction::I32GtS),
+        Opcode::I32GtU => (input, Instruction::I32GtU),
+        Opcode::I32LeS => (input, Instruction::I32LeS),
+        Opcode::I32LeU => (input, Instruction::I32LeU),
+        Opcode::I32GeS => (input, Instruction::I32GeS),
+        Opcode::I32GeU => (input, Instruction::I32GeU),
+        Opcode::I64Eqz => (input, Instruction::I64Eqz),
+        Opcode::I64Eq => (input, Instruction::I64Eq),
+        Opcode::I64Ne => (input, Instruction::I64Ne),
+        Opcode::I64LtS => (input, Instruction::I64LtS),
+        Opcode::I64LtU => (input, Instruction::I64LtU),
+        Opcode::I64GtS => (input, Instruction::I64GtS),
+        Opcode::I64GtU => (input, Instruction::I64GtU),
+        Opcode::I64LeS => (input, Instruction::I64LeS),
+        Opcode::I64LeU => (input, Instruction::I64LeU),
+        Opcode::I64GeS => (input, Instruction::I64GeS),
+        Opcode::I64GeU => (input, Instruction::I64GeU),
+        Opcode::F32Eq => (input, Instruction::F32Eq),
+        Opcode::F32Ne => (input, Instruction::F32Ne),
+        Opcode::F32Lt => (input, Instruction::F32Lt),
+        Opcode::F32Gt => (input, Instruction::F32Gt),
+        Opcode::F32Le => (input, Instruction::F32Le),
+        Opcode::F32Ge => (input, Instruction::F32Ge),
+        Opcode::F64Eq => (input, Instruction::F64Eq),
+        Opcode::F64Ne => (input, Instruction::F64Ne),
+        Opcode::F64Lt => (input, Instruction::F64Lt),
+        Opcode::F64Gt => (input, Instruction::F64Gt),
+        Opcode::F64Le => (input, Instruction::F64Le),
+        Opcode::F64Ge => (input, Instruction::F64Ge),
+        Opcode::I32Clz => (input, Instruction::I32Clz),
+        Opcode::I32Ctz => (input, Instruction::I32Ctz),
+        Opcode::I32Popcnt => (input, Instruction::I32Popcnt),
         Opcode::I32Add => (input, Instruction::I32Add),
+        Opcode::I32Sub => (input, Instruction::I32Sub),
+        Opcode::I32Mul => (input, Instruction::I32Mul),
+        Opcode::I32DivS => (input, Instruction::I32DivS),
+        Opcode::I32DivU => (input, Instruction::I32DivU),
+        Opcode::I32RemS => (input, Instruction::I32RemS),
+        Opcode::I32RemU => (input, Instruction::I32RemU),
+        Opcode::I32And => (input, Instruction::I32And),
+        Opcode::I32Or => (input, Instruction::I32Or),
+        Opcode::I32Xor => (input, Instruction::I32Xor),
+        Opcode::I32Shl => (input, Instruction::I32Shl),
+        Opcode::I32ShrS => (input, Instruction::I32ShrS),
+        Opcode::I32ShrU => (input, Instruction::I32ShrU),
+        Opcode::I32Rotl => (input, Instruction::I32Rotl),
+        Opcode::I32Rotr => (input, Instruction::I32Rotr),
+        Opcode::I64Clz => (input, Instruction::I64Clz),
+        Opcode::I64Ctz => (input, Instruction::I64Ctz),
+        Opcode::I64Popcnt => (input, Instruction::I64Popcnt),
         Opcode::I64Add => (input, Instruction::I64Add),
+        Opcode::I64Sub => (input, Instruction::I64Sub),
+        Opcode::I64Mul => (input, Instruction::I64Mul),
+        Opcode::I64DivS => (input, Instruction::I64DivS),
+        Opcode::I64DivU => (input, Instruction::I64DivU),
+        Opcode::I64RemS => (input, Instruction::I64RemS),
+        Opcode::I64RemU => (input, Instruction::I64RemU),
+        Opcode::I64And => (input, Instruction::I64And),
+        Opcode::I64Or => (input, Instruction::I64Or),
+        Opcode::I64Xor => (input, Instruction::I64Xor),
+        Opcode::I64Shl => (input, Instruction::I64Shl),
+        Opcode::I64ShrS => (input, Instruction::I64ShrS),
+        Opcode::I64ShrU => (input, Instruction::I64ShrU),
+        Opcode::I64Rotl => (input, Instruction::I64Rotl),
+        Opcode::I64Rotr => (input, Instruction::I64Rotr),
+        Opcode::F32Abs => (input, Instruction::F32Abs),
+        Opcode::F32Neg => (input, Instruction::F32Neg),
+        Opcode::F32Ceil => (input, Instruction::F32Ceil),
+        Opcode::F32Floor => (input, Instruction::F32Floor),
+        Opcode::F32Trunc => (input, Instruction::F32Trunc),
+        Opcode::F32Nearest => (input, Instruction::F32Nearest),
+        Opcode::F32Sqrt => (input, Instruction::F32Sqrt),
+        Opcode::F32Add => (input, Instruction::F32Add),
+        Opcode::F32Sub => (input, Instruction::F32Sub),
+        Opcode::F32Mul => (input, Instruction::F32Mul),
+        Opcode::F32Div => (input, Instruction::F32Div),
+        Opcode::F64Abs => (input, Instruction::F64Abs),
+        Opcode::F64Neg => (input, Instruction::F64Neg),
+        Opcode::F64Ceil => (input, Instruction::F64Ceil),
+        Opcode::F64Floor => (input, Instruction::F64Floor),
+        Opcode::F64Trunc => (input, Instruction::F64Trunc),
+        Opcode::F64Nearest => (input, Instruction::F64Nearest),
+        Opcode::F64Sqrt => (input, Instruction::F64Sqrt),
+        Opcode::F64Add => (input, Instruction::F64Add),
+        Opcode::F64Sub => (input, Instruction::F64Sub),
+        Opcode::F64Mul => (input, Instruction::F64Mul),
+        Opcode::F64Div => (input, Instruction::F64Div),
+        Opcode::I32WrapI64 => (input, Instruction::I32WrapI64),
+        Opcode::I32TruncF32S => (input, Instruction::I32TruncF32S),
+        Opcode::I32TruncF32U => (input, Instruction::I32TruncF32U),
+        Opcode::I32TruncF64S => (input, Instruction::I32TruncF64S),
+        Opcode::I32TruncF64U => (input, Instruction::I32TruncF64U),
+        Opcode::I64ExtendI32S => (input, Instruction::I64ExtendI32S),
+        Opcode::I64ExtendI32U => (input, Instruction::I64ExtendI32U),
+        Opcode::I64TruncF32S => (input, Instruction::I64TruncF32S),
+        Opcode::I64TruncF32U => (input, Instruction::I64TruncF32U),
+        Opcode::I64TruncF64S => (input, Instruction::I64TruncF64S),
+        Opcode::I64TruncF64U => (input, Instruction::I64TruncF64U),
+        Opcode::F32ConvertI32S => (input, Instruction::F32ConvertI32S),
+        Opcode::F32ConvertI32U => (input, Instruction::F32ConvertI32U),
+        Opcode::F32ConvertI64S => (input, Instruction::F32ConvertI64S),
+        Opcode::F32ConvertI64U => (input, Instruction::F32ConvertI64U),
+        Opcode::F32DemoteF64 => (input, Instruction::F32DemoteF64),
+        Opcode::F64ConvertI32S => (input, Instruction::F64ConvertI32S),
+        Opcode::F64ConvertI32U => (input, Instruction::F64ConvertI32U),
+        Opcode::F64ConvertI64S => (input, Instruction::F64ConvertI64S),
+        Opcode::F64ConvertI64U => (input, Instruction::F64ConvertI64U),
+        Opcode::F64PromoteF32 => (input, Instruction::F64PromoteF32),
+        Opcode::I32ReinterpretF32 => (input, Instruction::I32ReinterpretF32),
+        Opcode::I64ReinterpretF64 => (input, Instruction::I64ReinterpretF64),
+        Opcode::F32ReinterpretI32 => (input, Instruction::F32ReinterpretI32),
+        Opcode::F64ReinterpretI64 => (input, Instruction::F64ReinterpretI64),
+        Opcode::I32Extend8S => (input, Instruction::I32Extend8S),
+        Opcode::I32Extend16S => (input, Instruction::I32Extend16S),
+        Opcode::I64Extend8S => (input, Instruction::I64Extend8S),
+        Opcode::I64Extend16S => (input, Instruction::I64Extend16S),
+        Opcode::I64Extend32S => (input, Instruction::I64Extend32S),
+        Opcode::RefNull => {
+            let (rest, ref_type) = decode_value_type(input)?;
+            (rest, Instruction::RefNull(ref_type))
+        }
+        Opcode::RefIsNull => (input, Instruction::RefIsNull),
+        Opcode::RefFunc => {
+            let (rest, idx) = leb128_u32(input)?;
+            (rest, Instruction::RefFunc(idx))
+        }
+        Opcode::MiscPrefix => decode_misc_instruction(input)?,
     };
 
     Ok((rest, inst))
 }
 
+// 0xFCで始まる命令
+fn decode_misc_instruction(input: &[u8]) -> IResult<&[u8], Instruction> {
+    let (input, code) = leb128_u32(input)?;
+    let Some(op) = MiscOpcode::from_u32(code) else {
+        return Err(nom::Err::Failure(DecodeError::UnknownMiscOpcode(code)));
+    };
+
+    let (rest, inst) = match op {
+        MiscOpcode::I32TruncSatF32S => (input, Instruction::I32TruncSatF32S),
+        MiscOpcode::I32TruncSatF32U => (input, Instruction::I32TruncSatF32U),
+        MiscOpcode::I32TruncSatF64S => (input, Instruction::I32TruncSatF64S),
+        MiscOpcode::I32TruncSatF64U => (input, Instruction::I32TruncSatF64U),
+        MiscOpcode::I64TruncSatF32S => (input, Instruction::I64TruncSatF32S),
+        MiscOpcode::I64TruncSatF32U => (input, Instruction::I64TruncSatF32U),
+        MiscOpcode::I64TruncSatF64S => (input, Instruction::I64TruncSatF64S),
+        MiscOpcode::I64TruncSatF64U => (input, Instruction::I64TruncSatF64U),
+        MiscOpcode::MemoryInit => {
+            let (rest, data_idx) = leb128_u32(input)?;
+            let (rest, _) = le_u8(rest)?;
+            (rest, Instruction::MemoryInit(data_idx))
+        }
+        MiscOpcode::DataDrop => {
+            let (rest, data_idx) = leb128_u32(input)?;
+            (rest, Instruction::DataDrop(data_idx))
+        }
+        // メモリのインデックス(0のみ)を読み飛ばす
+        MiscOpcode::MemoryCopy => {
+            let (rest, _) = take(2usize)(input)?;
+            (rest, Instruction::MemoryCopy)
+        }
+        MiscOpcode::MemoryFill => {
+            let (rest, _) = le_u8(input)?;
+            (rest, Instruction::MemoryFill)
+        }
+    };
+    Ok((rest, inst))
+}
+
+fn decode_table_section(input: &[u8]) -> IResult<&[u8], Vec<Table>> {
+    let (mut input, count) = leb128_u32(input)?;
+    let mut tables = vec![];
+
+    for _ in 0..count {
+        // 要素の型(funcrefのみ)
+        let (rest, elem_type) = decode_elem_type(input)?;
+        let (rest, limits) = decode_limits(rest)?;
+        tables.push(Table { elem_type, limits });
+        input = rest;
+    }
+    Ok((input, tables))
+}
+
+fn decode_element_section(input: &[u8]) -> IResult<&[u8], Vec<Element>> {
+    let (mut input, count) = leb128_u32(input)?;
+    let mut elements = vec![];
+
+    for _ in 0..count {
+        // ビット0: passiveかdeclarative, ビット1: テーブルを指定する(activeの場合)かdeclarative,
+        // ビット2: 関数のインデックスではなく初期化式を並べる
+        let (rest, flags) = leb128_u32(input)?;
+        if flags > 0x07 {
+            return Err(nom::Err::Failure(DecodeError::InvalidElemFlags(flags)));
+        }
+        let (rest, mode) = match flags & 0x03 {
+            0x00 => {
+                let (rest, offset) = decode_const_expr(rest)?;
+                (
+                    rest,
+                    ElementMode::Active {
+                        table_idx: 0,
+                        offset,
+                    },
+                )
+            }
+            0x02 => {
+                let (rest, table_idx) = leb128_u32(rest)?;
+                let (rest, offset) = decode_const_expr(rest)?;
+                (rest, ElementMode::Active { table_idx, offset })
+            }
+            0x01 => (rest, ElementMode::Passive),
+            _ => (rest, ElementMode::Declarative),
+        };
+        let exprs = flags & 0x04 != 0;
+        // テーブル0へのactiveセグメント以外は要素の種類(0x00: funcref)か参照型が続く
+        let rest = match (flags & 0x03, exprs) {
+            (0x00, _) => rest,
+            (_, false) => tag([0x00])(rest)?.0,
+            (_, true) => decode_elem_type(rest)?.0,
+        };
+
+        let (mut rest, init_count) = leb128_u32(rest)?;
+        let mut init = vec![];
+        for _ in 0..init_count {
+            let (next, expr) = if exprs {
+                decode_const_expr(rest)?
+            } else {
+                let (next, func_idx) = leb128_u32(rest)?;
+                (next, ConstExpr::RefFunc(func_idx))
+            };
+            init.push(expr);
+            rest = next;
+        }
+
+        elements.push(Element { mode, init });
+        input = rest;
+    }
+    Ok((input, elements))
+}
+
+fn decode_data_section(input: &[u8]) -> IResult<&[u8], Vec<Data>> {
+    let (mut input, count) = leb128_u32(input)?;
+    let mut datas = vec![];
+
+    for _ in 0..count {
+        // 0x00: メモリ0へのactiveセグメント, 0x01: passiveセグメント,
+        // 0x02: メモリを指定したactiveセグメント
+        let (rest, flags) = leb128_u32(input)?;
+        let (rest, mode) = match flags {
+            0x00 => {
+                let (rest, offset) = decode_const_expr(rest)?;
+                (
+                    rest,
+                    DataMode::Active {
+                        memory_idx: 0,
+                        offset,
+                    },
+                )
+            }
+            0x01 => (rest, DataMode::Passive),
+            0x02 => {
+                let (rest, memory_idx) = leb128_u32(rest)?;
+                let (rest, offset) = decode_const_expr(rest)?;
+                (rest, DataMode::Active { memory_idx, offset })
+            }
+            _ => return Err(nom::Err::Failure(DecodeError::InvalidDataFlags(flags))),
+        };
+
+        let (rest, size) = leb128_u32(rest)?;
+        let (rest, init) = take(size)(rest)?;
+        datas.push(Data {
+            mode,
+            init: init.to_vec(),
+        });
+        input = rest;
+    }
+    Ok((input, datas))
+}
+
+fn decode_memory_section(input: &[u8]) -> IResult<&[u8], Vec<Memory>> {
+    let (mut input, count) = leb128_u32(input)?;
+    let mut memories = vec![];
+
+    for _ in 0..count {
+        let (rest, limits) = decode_limits(input)?;
+        memories.push(Memory { limits });
+        input = rest;
+    }
+    Ok((input, memories))
+}
+
+fn decode_limits(input: &[u8]) -> IResult<&[u8], Limits> {
+    // 0x00: 最小値のみ, 0x01: 最小値と最大値
+    let (input, flags) = le_u8(input)?;
+    let (input, min) = leb128_u32(input)?;
+    let (input, max) = if flags == 0x00 {
+        (input, None)
+    } else {
+        let (input, max) = leb128_u32(input)?;
+        (input, Some(max))
+    };
+    Ok((input, Limits { min, max }))
+}
+
+fn decode_global_section(input: &[u8]) -> IResult<&[u8], Vec<Global>> {
+    let (mut input, count) = leb128_u32(input)?;
+    let mut globals = vec![];
+
+    for _ in 0..count {
+        // グローバル変数の型
+        let (rest, value_type) = decode_value_type(input)?;
+        // 0x00: immutable, 0x01: mutable
+        let (rest, mutability) = le_u8(rest)?;
+        // 初期値
+        let (rest, init_expr) = decode_const_expr(rest)?;
+
+        globals.push(Global {
+            global_type: GlobalType {
+                value_type,
+                mutable: mutability == 0x01,
+            },
+            init_expr,
+        });
+        input = rest;
+    }
+    Ok((input, globals))
+}
+
+fn decode_const_expr(input: &[u8]) -> IResult<&[u8], ConstExpr> {
+    let (input, op) = le_u8(input)?;
+    let (input, expr) = match op {
+        // i32.const
+        0x41 => {
+            let (rest, value) = leb128_i32(input)?;
+            (rest, ConstExpr::I32Const(value))
+        }
+        // i64.const
+        0x42 => {
+            let (rest, value) = leb128_i64(input)?;
+            (rest, ConstExpr::I64Const(value))
+        }
+        // f32.const
+        0x43 => {
+            let (rest, bits) = le_u32(input)?;
+            (rest, ConstExpr::F32Const(f32::from_bits(bits)))
+        }
+        // f64.const
+        0x44 => {
+            let (rest, bits) = le_u64(input)?;
+            (rest, ConstExpr::F64Const(f64::from_bits(bits)))
+        }
+        // ref.null
+        0xD0 => {
+            let (rest, ref_type) = decode_value_type(input)?;
+            (rest, ConstExpr::RefNull(ref_type))
+        }
+        // ref.func
+        0xD2 => {
+            let (rest, idx) = leb128_u32(input)?;
+            (rest, ConstExpr::RefFunc(idx))
+        }
+        // global.get
+        0x23 => {
+            let (rest, idx) = leb128_u32(input)?;
+            (rest, ConstExpr::GlobalGet(idx))
+        }
+        _ => return Err(nom::Err::Failure(DecodeError::InvalidConstExpr(op))),
+    };
+    // 初期化式はendで終わる
+    let (input, _) = tag([Opcode::End as u8])(input)?;
+    Ok((input, expr))
+}
+
+fn decode_block_type(input: &[u8]) -> IResult<&[u8], BlockType> {
+    let (rest, byte) = le_u8(input)?;
+    match byte {
+        0x40 => Ok((rest, BlockType::Void)),
+        // 0x7F: i32, 0x7E: i64, 0x7D: f32, 0x7C: f64
+        0x7C..=0x7F | 0x70 => {
+            let (rest, value_type) = decode_value_type(input)?;
+            Ok((rest, BlockType::Value(value_type)))
+        }
+        // それ以外はType Sectionのインデックス(符号付きLEB128)
+        _ => {
+            let (rest, idx) = leb128_i64(input)?;
+            Ok((rest, BlockType::TypeIndex(idx as u32)))
+        }
+    }
+}
+
+// メモリアクセス命令の即値(アライメントとオフセット)
+fn decode_memarg(input: &[u8]) -> IResult<&[u8], (u32, u32)> {
+    let (input, align) = leb128_u32(input)?;
+    let (input, offset) = leb128_u32(input)?;
+    Ok((input, (align, offset)))
+}
+
 fn decode_export_section(input: &[u8]) -> IResult<&[u8], Vec<Export>> {
     // エクスポートの要素数
     let (mut input, count) = leb128_u32(input)?;
     let mut exports = vec![];
 
     for _ in 0..count {
-        // エクスポート名のバイト列の長さ
-        let (rest, name_len) = leb128_u32(input)?;
-        // バイト列の長さ分だけ読み取る
-        let (rest, name_bytes) = take(name_len)(rest)?;
-        // バイト列を文字列に変換
-        let name = String::from_utf8(name_bytes.to_vec()).expect("invalid utf-8 string");
+        // エクスポート名
+        let (rest, name) = decode_name(input)?;
 
         // エクスポートの種類
         let (rest, export_kind) = le_u8(rest)?;
@@ -238,7 +1123,14 @@ fn decode_export_section(input: &[u8]) -> IResult<&[u8], Vec<Export>> {
 
         let desc = match export_kind {
             0x00 => ExportDesc::Func(idx),
-            _ => unimplemented!("unsupported export kind: {:x}", export_kind),
+            0x01 => ExportDesc::Table(idx),
+            0x02 => ExportDesc::Memory(idx),
+            0x03 => ExportDesc::Global(idx),
+            _ => {
+                return Err(nom::Err::Failure(DecodeError::InvalidExportKind(
+                    export_kind,
+                )))
+            }
         };
 
         exports.push(Export { name, desc });
@@ -249,15 +1141,20 @@ fn decode_export_section(input: &[u8]) -> IResult<&[u8], Vec<Export>> {
 
 #[cfg(test)]
 mod tests {
-    use std::vec;
-
     use crate::binary::{
-        instruction::Instruction,
-        module::Module,
+        instruction::{BrTable, Instruction},
+        module::{Module, ModuleStats},
         section::Function,
-        types::{Export, ExportDesc, FuncType, FunctionLocal, ValueType},
+        types::{
+            BlockType, ConstExpr, Data, DataMode, ElemType, Element, ElementMode, Export,
+            ExportDesc, FuncType, FunctionLocal, Global, GlobalType, Import, ImportDesc, Limits,
+            Memory, Table, ValueType,
+        },
     };
+    use alloc::{boxed::Box, collections::BTreeMap, string::ToString, vec, vec::Vec};
     use anyhow::Result;
+    #[cfg(feature = "std")]
+    use std::io::{Cursor, Read};
 
     #[test]
     fn decode_simplest_module() -> Result<()> {
@@ -285,6 +1182,31 @@ mod tests {
         );
         Ok(())
     }
+
+    // watもファイルの読み込みも使わないので、stdなしのビルドでもデコードを確かめられる
+    #[test]
+    fn decode_bytes() -> Result<()> {
+        let module = Module::new(include_bytes!("../fixtures/func_add.wasm"))?;
+        assert_eq!(
+            module.type_section,
+            Some(vec![FuncType {
+                params: vec![ValueType::I32, ValueType::I32],
+                results: vec![ValueType::I32],
+            }])
+        );
+        assert_eq!(
+            module.instructions(0),
+            Some(
+                &[
+                    Instruction::LocalGet(0),
+                    Instruction::LocalGet(1),
+                    Instruction::I32Add,
+                    Instruction::End,
+                ][..]
+            )
+        );
+        Ok(())
+    }
     #[test]
     fn decode_func_param() -> Result<()> {
         let wasm = wat::parse_str("(module (func (param i32 i64)))")?;
@@ -393,6 +1315,39 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn decode_export_kinds() -> Result<()> {
+        let wasm = wat::parse_str(
+            r#"(module
+                (table 1 funcref)
+                (memory 1)
+                (global i32 (i32.const 0))
+                (export "tab" (table 0))
+                (export "mem" (memory 0))
+                (export "g" (global 0))
+            )"#,
+        )?;
+        let module = Module::new(&wasm)?;
+        assert_eq!(
+            module.export_section,
+            Some(vec![
+                Export {
+                    name: "tab".to_string(),
+                    desc: ExportDesc::Table(0),
+                },
+                Export {
+                    name: "mem".to_string(),
+                    desc: ExportDesc::Memory(0),
+                },
+                Export {
+                    name: "g".to_string(),
+                    desc: ExportDesc::Global(0),
+                },
+            ])
+        );
+        Ok(())
+    }
+
     #[test]
     fn decode_i64_const() -> Result<()> {
         let wasm = wat::parse_file("src/fixtures/i64_const.wat")?;
@@ -414,4 +1369,641 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn decode_global() -> Result<()> {
+        let wasm = wat::parse_str(
+            "(module (global (mut i32) (i32.const 7)) (global i64 (i64.const -1)))",
+        )?;
+        let module = Module::new(&wasm)?;
+        assert_eq!(
+            module,
+            Module {
+                global_section: Some(vec![
+                    Global {
+                        global_type: GlobalType {
+                            value_type: ValueType::I32,
+                            mutable: true,
+                        },
+                        init_expr: ConstExpr::I32Const(7),
+                    },
+                    Global {
+                        global_type: GlobalType {
+                            value_type: ValueType::I64,
+                            mutable: false,
+                        },
+                        init_expr: ConstExpr::I64Const(-1),
+                    },
+                ]),
+                ..Default::default()
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn decode_memory() -> Result<()> {
+        let tests = vec![
+            ("(module (memory 1))", Limits { min: 1, max: None }),
+            (
+                "(module (memory 1 2))",
+                Limits {
+                    min: 1,
+                    max: Some(2),
+                },
+            ),
+        ];
+        for (wat, limits) in tests {
+            let module = Module::new(&wat::parse_str(wat)?)?;
+            assert_eq!(
+                module,
+                Module {
+                    memory_section: Some(vec![Memory { limits }]),
+                    ..Default::default()
+                }
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn decode_block_type() -> Result<()> {
+        let wasm = wat::parse_str(
+            "(module (func (block) (block (result i64) (i64.const 1)) (block (param i32) (result i32))))",
+        )?;
+        let module = Module::new(&wasm)?;
+        let code = &module.code_section.unwrap()[0].code;
+        assert_eq!(code[0], Instruction::Block(BlockType::Void));
+        assert_eq!(
+            code[2],
+            Instruction::Block(BlockType::Value(ValueType::I64))
+        );
+        assert_eq!(code[5], Instruction::Block(BlockType::TypeIndex(1)));
+        Ok(())
+    }
+
+    #[test]
+    fn decode_br_table() -> Result<()> {
+        let wasm =
+            wat::parse_str("(module (func (block (block (br_table 1 0 1 (i32.const 0))))))")?;
+        let module = Module::new(&wasm)?;
+        let code = &module.code_section.unwrap()[0].code;
+        assert_eq!(
+            code[3],
+            Instruction::BrTable(Box::new(BrTable {
+                targets: vec![1, 0],
+                default: 1,
+            }))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn decode_custom_name_section() -> Result<()> {
+        // 名前付きの関数があると、末尾にCustom Section("name")が出力される
+        let wasm = wat::parse_str("(module (func $f (export \"f\")))")?;
+        assert!(wasm.windows(4).any(|w| w == b"name"));
+        let module = Module::new(&wasm)?;
+        assert_eq!(
+            module,
+            Module {
+                type_section: Some(vec![FuncType::default()]),
+                function_section: Some(vec![0]),
+                code_section: Some(vec![Function {
+                    locals: vec![],
+                    code: vec![Instruction::End],
+                }]),
+                export_section: Some(vec![Export {
+                    name: "f".to_string(),
+                    desc: ExportDesc::Func(0),
+                }]),
+                name_section: Some(BTreeMap::from([(0, "f".to_string())])),
+                ..Default::default()
+            }
+        );
+
+        // 名前のない関数は含まれない
+        let wasm = wat::parse_str(
+            "(module (func) (func $factorial (param i64) (result i64) (local.get 0)))",
+        )?;
+        let module = Module::new(&wasm)?;
+        assert_eq!(
+            module.name_section,
+            Some(BTreeMap::from([(1, "factorial".to_string())]))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn decode_skip_custom_section() -> Result<()> {
+        // "name"以外のCustom Sectionは読み飛ばす
+        let mut wasm = wat::parse_str("(module (func))")?;
+        wasm.extend([0x00, 0x04, 0x03, b'f', b'o', b'o']);
+        let module = Module::new(&wasm)?;
+        assert_eq!(module.function_section, Some(vec![0]));
+        assert_eq!(module.name_section, None);
+        Ok(())
+    }
+
+    #[test]
+    fn decode_data_section() -> Result<()> {
+        let wasm = wat::parse_str(
+            r#"(module
+                (memory 1)
+                (data (i32.const 4) "ab")
+                (data "cd")
+                (func (memory.init 1 (i32.const 0) (i32.const 0) (i32.const 2)) (data.drop 1))
+            )"#,
+        )?;
+        let module = Module::new(&wasm)?;
+        assert_eq!(module.data_count_section, Some(2));
+        assert_eq!(
+            module.data_section,
+            Some(vec![
+                Data {
+                    mode: DataMode::Active {
+                        memory_idx: 0,
+                        offset: ConstExpr::I32Const(4),
+                    },
+                    init: b"ab".to_vec(),
+                },
+                Data {
+                    mode: DataMode::Passive,
+                    init: b"cd".to_vec(),
+                },
+            ])
+        );
+        let code = module.instructions(0).unwrap_or_default();
+        assert_eq!(code[3], Instruction::MemoryInit(1));
+        assert_eq!(code[4], Instruction::DataDrop(1));
+        Ok(())
+    }
+
+    #[test]
+    fn decode_unknown_opcode() {
+        let wasm = [
+            0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // magic, version
+            0x01, 0x04, 0x01, 0x60, 0x00, 0x00, // type section: () -> ()
+            0x03, 0x02, 0x01, 0x00, // function section
+            0x0a, 0x05, 0x01, 0x03, 0x00, 0xff, 0x0b, // code section: 0xff, end
+        ];
+        let err = Module::new(&wasm).unwrap_err();
+        assert!(err.to_string().contains("0xff"), "{err}");
+    }
+
+    #[test]
+    fn decode_unknown_section_code() {
+        let wasm = [
+            0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // magic, version
+            0x20, 0x00, // section id 0x20, size 0
+        ];
+        let err = Module::new(&wasm).unwrap_err();
+        assert!(err.to_string().contains("0x20"), "{err}");
+    }
+
+    #[test]
+    fn decode_invalid_section_order() {
+        let tests = vec![
+            (
+                vec![
+                    0x01, 0x01, 0x00, // type section (0 types)
+                    0x01, 0x01, 0x00, // type section (0 types)
+                ],
+                "duplicate section: 0x01",
+            ),
+            (
+                vec![
+                    0x0a, 0x01, 0x00, // code section (0 functions)
+                    0x03, 0x01, 0x00, // function section (0 functions)
+                ],
+                "section out of order: 0x03",
+            ),
+        ];
+        for (sections, want) in tests {
+            let mut wasm = vec![0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+            wasm.extend(sections);
+            let err = Module::new(&wasm).unwrap_err();
+            assert!(err.to_string().contains(want), "{err}");
+        }
+    }
+
+    #[test]
+    fn decode_version() {
+        let wasm = [0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+        assert_eq!(Module::new(&wasm).ok(), Some(Module::default()));
+
+        let wasm = [0x00, 0x61, 0x73, 0x6d, 0x02, 0x00, 0x00, 0x00];
+        let err = Module::new(&wasm).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "failed to parse wasm: unsupported wasm version: 2"
+        );
+    }
+
+    #[test]
+    fn decode_invalid_func_type_form() {
+        let wasm = [
+            0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // magic, version
+            0x01, 0x04, 0x01, 0x5f, 0x00, 0x00, // type section: 1 type, form 0x5f
+        ];
+        let err = Module::new(&wasm).unwrap_err();
+        assert!(err.to_string().contains("0x5f"), "{err}");
+    }
+
+    #[test]
+    fn decode_invalid_value_type() {
+        let wasm = [
+            0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // magic, version
+            0x01, 0x05, 0x01, 0x60, 0x01, 0x10, 0x00, // type section: (param 0x10)
+        ];
+        let err = Module::new(&wasm).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "failed to parse wasm: invalid value type: 0x10"
+        );
+
+        let wasm = [
+            0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // magic, version
+            0x04, 0x04, 0x01, 0x6f, 0x00, 0x01, // table section: elem type 0x6f
+        ];
+        let err = Module::new(&wasm).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "failed to parse wasm: invalid elem type: 0x6f"
+        );
+    }
+
+    #[test]
+    fn decode_invalid_import() {
+        let wasm = [
+            0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // magic, version
+            0x02, 0x06, 0x01, 0x01, b'a', 0x01, b'b', 0x04, // import section: kind 0x04
+        ];
+        let err = Module::new(&wasm).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "failed to parse wasm: invalid import kind: 0x04"
+        );
+
+        let wasm = [
+            0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // magic, version
+            0x07, 0x05, 0x01, 0x01, 0xff, 0x00, 0x00, // export section: name "\xff"
+        ];
+        let err = Module::new(&wasm).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "failed to parse wasm: malformed UTF-8 encoding"
+        );
+    }
+
+    #[test]
+    fn decode_invalid_export_kind() {
+        let wasm = [
+            0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // magic, version
+            0x07, 0x05, 0x01, 0x01, b'f', 0x04, 0x00, // export section: kind 0x04
+        ];
+        let err = Module::new(&wasm).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "failed to parse wasm: invalid export kind: 0x04"
+        );
+    }
+
+    #[test]
+    fn decode_invalid_data_flags() {
+        let wasm = [
+            0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // magic, version
+            0x0b, 0x03, 0x01, 0x03, 0x00, // data section: flags 0x03
+        ];
+        let err = Module::new(&wasm).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "failed to parse wasm: invalid data segment flags: 0x03"
+        );
+    }
+
+    #[test]
+    fn decode_invalid_const_expr() {
+        let wasm = [
+            0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // magic, version
+            0x06, 0x05, 0x01, 0x7f, 0x00, 0x6a, 0x0b, // global section: (i32.add)
+        ];
+        let err = Module::new(&wasm).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "failed to parse wasm: constant expression required: 0x6a"
+        );
+    }
+
+    #[test]
+    fn decode_start() -> Result<()> {
+        let wasm = wat::parse_str("(module (func) (func) (start 1))")?;
+        let module = Module::new(&wasm)?;
+        assert_eq!(module.start_section, Some(1));
+        Ok(())
+    }
+
+    #[test]
+    fn decode_table() -> Result<()> {
+        let wasm = wat::parse_str("(module (table 2 10 funcref))")?;
+        let module = Module::new(&wasm)?;
+        assert_eq!(
+            module.table_section,
+            Some(vec![Table {
+                elem_type: ElemType::FuncRef,
+                limits: Limits {
+                    min: 2,
+                    max: Some(10)
+                },
+            }])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn decode_element() -> Result<()> {
+        let wasm = wat::parse_str("(module (table 2 funcref) (func) (elem (i32.const 1) 0 0))")?;
+        let module = Module::new(&wasm)?;
+        assert_eq!(
+            module.element_section,
+            Some(vec![Element {
+                mode: ElementMode::Active {
+                    table_idx: 0,
+                    offset: ConstExpr::I32Const(1),
+                },
+                init: vec![ConstExpr::RefFunc(0), ConstExpr::RefFunc(0)],
+            }])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn decode_element_modes() -> Result<()> {
+        let wasm = wat::parse_str(
+            r#"(module
+                (table 2 funcref)
+                (func $f)
+                (elem func $f)
+                (elem declare func $f)
+                (elem (table 0) (i32.const 0) funcref (ref.null func) (ref.func $f))
+                (elem funcref (ref.func $f)))"#,
+        )?;
+        let module = Module::new(&wasm)?;
+        let active = ElementMode::Active {
+            table_idx: 0,
+            offset: ConstExpr::I32Const(0),
+        };
+        let modes: Vec<_> = module
+            .element_section
+            .iter()
+            .flatten()
+            .map(|element| (element.mode.clone(), element.init.clone()))
+            .collect();
+        assert_eq!(
+            modes,
+            vec![
+                (ElementMode::Passive, vec![ConstExpr::RefFunc(0)]),
+                (ElementMode::Declarative, vec![ConstExpr::RefFunc(0)]),
+                (
+                    active,
+                    vec![
+                        ConstExpr::RefNull(ValueType::FuncRef),
+                        ConstExpr::RefFunc(0)
+                    ]
+                ),
+                (ElementMode::Passive, vec![ConstExpr::RefFunc(0)]),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn decode_invalid_elem_flags() {
+        let wasm = [
+            0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // magic, version
+            0x09, 0x02, 0x01, 0x08, // element section: flags 0x08
+        ];
+        let err = Module::new(&wasm).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "failed to parse wasm: invalid elem segment flags: 0x08"
+        );
+    }
+
+    #[test]
+    fn decode_call_indirect() -> Result<()> {
+        let wasm = wat::parse_str(
+            "(module (type (func)) (table 1 funcref) (func (call_indirect (type 0) (i32.const 0))))",
+        )?;
+        let module = Module::new(&wasm)?;
+        assert_eq!(
+            module.code_section.unwrap()[0].code,
+            vec![
+                Instruction::I32Const(0),
+                Instruction::CallIndirect {
+                    type_idx: 0,
+                    table_idx: 0
+                },
+                Instruction::End,
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn decode_float_const() -> Result<()> {
+        let wasm =
+            wat::parse_str("(module (func (result f32 f64) f32.const -0.25 f64.const 1.5))")?;
+        let module = Module::new(&wasm)?;
+        let code = &module.code_section.unwrap()[0].code;
+        assert_eq!(code[0], Instruction::F32Const(-0.25));
+        assert_eq!(code[1], Instruction::F64Const(1.5));
+        Ok(())
+    }
+
+    #[test]
+    fn decode_import() -> Result<()> {
+        let wasm = wat::parse_str(
+            r#"(module
+                (import "env" "add" (func (param i32 i32) (result i32)))
+                (import "env" "counter" (global (mut i64)))
+                (import "js" "mem" (memory 1)))"#,
+        )?;
+        let module = Module::new(&wasm)?;
+        assert_eq!(
+            module.import_section,
+            Some(vec![
+                Import {
+                    module: "env".to_string(),
+                    field: "add".to_string(),
+                    desc: ImportDesc::Func(0),
+                },
+                Import {
+                    module: "env".to_string(),
+                    field: "counter".to_string(),
+                    desc: ImportDesc::Global(GlobalType {
+                        value_type: ValueType::I64,
+                        mutable: true,
+                    }),
+                },
+                Import {
+                    module: "js".to_string(),
+                    field: "mem".to_string(),
+                    desc: ImportDesc::Memory(Memory {
+                        limits: Limits { min: 1, max: None },
+                    }),
+                },
+            ])
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn decode_from_file() -> Result<()> {
+        let from_wat = Module::from_file("src/fixtures/func_add.wat")?;
+        let from_wasm = Module::from_file("src/fixtures/func_add.wasm")?;
+        assert_eq!(from_wat, from_wasm);
+        assert!(Module::from_file("src/fixtures/not_found.wasm").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn module_instructions() -> Result<()> {
+        let module = Module::new(&wat::parse_file("src/fixtures/func_add.wat")?)?;
+        assert_eq!(
+            module.instructions(0),
+            Some(
+                &[
+                    Instruction::LocalGet(0),
+                    Instruction::LocalGet(1),
+                    Instruction::I32Add,
+                    Instruction::End,
+                ][..]
+            )
+        );
+        assert_eq!(module.instructions(1), None);
+
+        let module = Module::new(&wat::parse_file("src/fixtures/import_func.wat")?)?;
+        assert_eq!(module.instructions(0), None);
+        assert!(module.instructions(1).is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn module_stats() -> Result<()> {
+        let module = Module::new(&wat::parse_file("src/fixtures/func_add.wat")?)?;
+        assert_eq!(
+            module.stats(),
+            ModuleStats {
+                types: 1,
+                functions: 1,
+                instructions: 4,
+                ..Default::default()
+            }
+        );
+
+        let module = Module::new(&wat::parse_file("src/fixtures/wasi_hello.wat")?)?;
+        let stats = module.stats();
+        assert_eq!(stats.imports, 1);
+        assert_eq!(stats.memory_pages, 1);
+        assert_eq!(Module::default().stats(), ModuleStats::default());
+        Ok(())
+    }
+
+    #[test]
+    fn decode_negative_const() -> Result<()> {
+        // 即値は符号付きLEB128
+        let wasm = wat::parse_str(
+            "(module (func (result i32) i32.const -5) (func (result i64) i64.const -5))",
+        )?;
+        let module = Module::new(&wasm)?;
+        assert_eq!(
+            module.instructions(0),
+            Some(&[Instruction::I32Const(-5), Instruction::End][..])
+        );
+        assert_eq!(
+            module.instructions(1),
+            Some(&[Instruction::I64Const(-5), Instruction::End][..])
+        );
+        Ok(())
+    }
+
+    // 1回の読み取りで少しずつしか返さないReader
+    #[cfg(feature = "std")]
+    struct ChunkReader<R> {
+        inner: R,
+        chunk: usize,
+    }
+
+    #[cfg(feature = "std")]
+    impl<R: Read> Read for ChunkReader<R> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let len = buf.len().min(self.chunk);
+            self.inner.read(&mut buf[..len])
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn decode_reader() -> Result<()> {
+        for path in [
+            "src/fixtures/func_fib.wat",
+            "src/fixtures/memory_init.wat",
+            "src/fixtures/call_indirect.wat",
+        ] {
+            let wasm = wat::parse_file(path)?;
+            let reader = ChunkReader {
+                inner: Cursor::new(wasm.clone()),
+                chunk: 3,
+            };
+            assert_eq!(
+                Module::decode_reader(reader)?,
+                Module::new(&wasm)?,
+                "{path}"
+            );
+        }
+
+        // 途中で終わっている
+        let wasm = wat::parse_file("src/fixtures/func_add.wat")?;
+        let err = Module::decode_reader(Cursor::new(&wasm[..wasm.len() - 1])).unwrap_err();
+        assert!(err.to_string().contains("incomplete"), "{err}");
+        Ok(())
+    }
+
+    #[test]
+    fn decode_global_get_init() -> Result<()> {
+        let wasm = wat::parse_str(
+            r#"(module (global (import "env" "g") i64) (global i64 (global.get 0)))"#,
+        )?;
+        let module = Module::new(&wasm)?;
+        let globals = module.global_section.unwrap_or_default();
+        assert_eq!(globals[0].init_expr, ConstExpr::GlobalGet(0));
+        Ok(())
+    }
+
+    // 組み込んだ側の標準出力を汚さないよう、テスト以外のコードに出力のマクロを残さない
+    #[cfg(feature = "std")]
+    #[test]
+    fn no_debug_output() -> Result<()> {
+        fn check(dir: &std::path::Path) -> Result<()> {
+            for entry in std::fs::read_dir(dir)? {
+                let path = entry?.path();
+                if path.is_dir() {
+                    check(&path)?;
+                    continue;
+                }
+                if path.extension().is_none_or(|ext| ext != "rs") {
+                    continue;
+                }
+                let source = std::fs::read_to_string(&path)?;
+                let code = source.split("#[cfg(test)]").next().unwrap_or_default();
+                for mac in ["println!", "print!", "eprintln!", "eprint!", "dbg!"] {
+                    assert!(!code.contains(mac), "{} contains {mac}", path.display());
+                }
+            }
+            Ok(())
+        }
+        check(std::path::Path::new("src"))
+    }
 }
@@ -1,19 +1,47 @@
 use num_derive::FromPrimitive;
 
 use super::{instruction::Instruction, types::FunctionLocal};
+use alloc::vec::Vec;
 
-#[derive(Debug, PartialEq, Eq, FromPrimitive)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromPrimitive)]
 pub enum SectionCode {
+    Custom = 0x00,
     Type = 0x01,
     Import = 0x02,
     Function = 0x03,
+    Table = 0x04,
     Memory = 0x05,
+    Global = 0x06,
     Export = 0x07,
+    Start = 0x08,
+    Element = 0x09,
     Code = 0x0a,
     Data = 0x0b,
+    DataCount = 0x0c,
 }
 
-#[derive(Default, Debug, Clone, PartialEq, Eq)]
+impl SectionCode {
+    // 仕様で定められたセクションの並び順(DataCountはElementとCodeの間)
+    pub fn order(&self) -> u8 {
+        match self {
+            SectionCode::Custom => 0,
+            SectionCode::Type => 1,
+            SectionCode::Import => 2,
+            SectionCode::Function => 3,
+            SectionCode::Table => 4,
+            SectionCode::Memory => 5,
+            SectionCode::Global => 6,
+            SectionCode::Export => 7,
+            SectionCode::Start => 8,
+            SectionCode::Element => 9,
+            SectionCode::DataCount => 10,
+            SectionCode::Code => 11,
+            SectionCode::Data => 12,
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq)]
 pub struct Function {
     pub locals: Vec<FunctionLocal>,
     pub code: Vec<Instruction>,
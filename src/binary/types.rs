@@ -1,25 +1,65 @@
+use super::error::DecodeError;
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct FuncType {
     pub params: Vec<ValueType>,
     pub results: Vec<ValueType>,
 }
 
+impl FuncType {
+    // 引数と戻り値の型が一致すれば同じシグネチャとみなす
+    pub fn matches(&self, other: &FuncType) -> bool {
+        self.params == other.params && self.results == other.results
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ValueType {
-    I32, // 0x7F
-    I64, // 0x7E
+    I32,     // 0x7F
+    I64,     // 0x7E
+    F32,     // 0x7D
+    F64,     // 0x7C
+    FuncRef, // 0x70
 }
 
-impl From<u8> for ValueType {
-    fn from(value: u8) -> Self {
+impl TryFrom<u8> for ValueType {
+    type Error = DecodeError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
         match value {
-            0x7F => ValueType::I32,
-            0x7E => ValueType::I64,
-            _ => panic!("Invalid value type: {:x}", value),
+            0x7F => Ok(ValueType::I32),
+            0x7E => Ok(ValueType::I64),
+            0x7D => Ok(ValueType::F32),
+            0x7C => Ok(ValueType::F64),
+            0x70 => Ok(ValueType::FuncRef),
+            _ => Err(DecodeError::InvalidValueType(value)),
         }
     }
 }
 
+impl fmt::Display for ValueType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ValueType::I32 => "i32",
+            ValueType::I64 => "i64",
+            ValueType::F32 => "f32",
+            ValueType::F64 => "f64",
+            ValueType::FuncRef => "funcref",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// ブロックの型
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockType {
+    Void,             // 引数も戻り値もない
+    Value(ValueType), // 戻り値が1つ
+    TypeIndex(u32),   // Type Sectionの関数シグネチャ
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FunctionLocal {
     pub type_count: u32,       // ローカル変数の個数
@@ -29,6 +69,9 @@ pub struct FunctionLocal {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ExportDesc {
     Func(u32),
+    Table(u32),
+    Memory(u32),
+    Global(u32),
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -36,3 +79,143 @@ pub struct Export {
     pub name: String,
     pub desc: ExportDesc,
 }
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstExpr {
+    I32Const(i32),
+    I64Const(i64),
+    F32Const(f32),
+    F64Const(f64),
+    RefNull(ValueType), // 参照の型(funcrefのみ)
+    RefFunc(u32),       // 関数のインデックス
+    GlobalGet(u32),     // インポートしたグローバル変数の値
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlobalType {
+    pub value_type: ValueType,
+    pub mutable: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Global {
+    pub global_type: GlobalType,
+    pub init_expr: ConstExpr, // 初期値
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Limits {
+    pub min: u32,         // 最小サイズ
+    pub max: Option<u32>, // 最大サイズ
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Memory {
+    pub limits: Limits, // ページ数
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ElemType {
+    FuncRef, // 0x70
+}
+
+impl TryFrom<u8> for ElemType {
+    type Error = DecodeError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x70 => Ok(ElemType::FuncRef),
+            _ => Err(DecodeError::InvalidElemType(value)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Table {
+    pub elem_type: ElemType, // 要素の型
+    pub limits: Limits,      // 要素数
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ElementMode {
+    Passive,                                      // table.initで書き込む
+    Active { table_idx: u32, offset: ConstExpr }, // インスタンス化時に書き込む
+    Declarative,                                  // ref.funcで参照する関数の宣言のみ
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Element {
+    pub mode: ElementMode,
+    pub init: Vec<ConstExpr>, // ref.funcまたはref.null
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DataMode {
+    Passive,                                       // memory.initで書き込む
+    Active { memory_idx: u32, offset: ConstExpr }, // インスタンス化時に書き込む
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Data {
+    pub mode: DataMode,
+    pub init: Vec<u8>, // 書き込むバイト列
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportDesc {
+    Func(u32), // Type Sectionのインデックス
+    Table(Table),
+    Memory(Memory),
+    Global(GlobalType),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Import {
+    pub module: String, // モジュール名
+    pub field: String,  // インポートする名前
+    pub desc: ImportDesc,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FuncType, ValueType};
+    use alloc::{
+        string::{String, ToString},
+        vec,
+        vec::Vec,
+    };
+
+    #[test]
+    fn display_value_type() {
+        let types = [
+            ValueType::I32,
+            ValueType::I64,
+            ValueType::F32,
+            ValueType::F64,
+            ValueType::FuncRef,
+        ];
+        let names: Vec<String> = types.iter().map(|t| t.to_string()).collect();
+        assert_eq!(names, vec!["i32", "i64", "f32", "f64", "funcref"]);
+    }
+
+    #[test]
+    fn func_type_matches() {
+        let add = FuncType {
+            params: vec![ValueType::I32, ValueType::I32],
+            results: vec![ValueType::I32],
+        };
+        assert!(add.matches(&add.clone()));
+
+        let sub = FuncType {
+            params: vec![ValueType::I32, ValueType::I64],
+            results: vec![ValueType::I32],
+        };
+        assert!(!add.matches(&sub));
+
+        let void = FuncType {
+            params: vec![ValueType::I32, ValueType::I32],
+            results: vec![],
+        };
+        assert!(!add.matches(&void));
+    }
+}
@@ -0,0 +1,713 @@
+use super::{
+    instruction::Instruction,
+    module::Module,
+    types::{BlockType, ConstExpr, ElementMode, FuncType, GlobalType, ImportDesc, ValueType},
+};
+use alloc::{vec, vec::Vec};
+use anyhow::{anyhow, bail, Result};
+use ValueType::{FuncRef, F32, F64, I32, I64};
+
+// 関数本体から参照できるモジュールの情報
+struct Context<'a> {
+    types: &'a [FuncType],
+    funcs: Vec<&'a FuncType>,
+    globals: Vec<&'a GlobalType>,
+    tables: usize,
+    memories: usize,
+    datas: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CtrlKind {
+    Block,
+    Loop,
+    If,
+    Else,
+}
+
+// 制御命令ごとのフレーム
+struct CtrlFrame {
+    kind: CtrlKind,
+    params: Vec<ValueType>,
+    results: Vec<ValueType>,
+    height: usize,     // ブロック開始時の値スタックの高さ
+    unreachable: bool, // br, unreachableなどの後はスタックの型を問わない
+}
+
+impl Module {
+    // 各関数の本体を型検査する
+    pub fn validate(&self) -> Result<()> {
+        let types = self.type_section.as_deref().unwrap_or_default();
+        let mut ctx = Context {
+            types,
+            funcs: vec![],
+            globals: vec![],
+            tables: self.table_section.as_ref().map_or(0, |t| t.len()),
+            memories: self.memory_section.as_ref().map_or(0, |m| m.len()),
+            datas: self
+                .data_count_section
+                .map(|count| count as usize)
+                .unwrap_or(self.data_section.as_ref().map_or(0, |d| d.len())),
+        };
+
+        // インポートしたものは定義したものより前のインデックスを占める
+        for import in self.import_section.iter().flatten() {
+            match import.desc {
+                ImportDesc::Func(type_idx) => {
+                    let Some(func_type) = types.get(type_idx as usize) else {
+                        bail!("unknown type: {}", type_idx)
+                    };
+                    ctx.funcs.push(func_type);
+                }
+                ImportDesc::Table(_) => ctx.tables += 1,
+                ImportDesc::Memory(_) => ctx.memories += 1,
+                ImportDesc::Global(ref global_type) => ctx.globals.push(global_type),
+            }
+        }
+        let import_count = ctx.funcs.len();
+
+        let func_type_idxs = self.function_section.as_deref().unwrap_or_default();
+        for type_idx in func_type_idxs {
+            let Some(func_type) = types.get(*type_idx as usize) else {
+                bail!("unknown type: {}", type_idx)
+            };
+            ctx.funcs.push(func_type);
+        }
+        let import_globals = ctx.globals.len();
+        for global in self.global_section.iter().flatten() {
+            let init_type = ctx.const_expr_type(&global.init_expr, import_globals)?;
+            if init_type != global.global_type.value_type {
+                bail!(
+                    "type mismatch in global initializer: expected {:?}, found {:?}",
+                    global.global_type.value_type,
+                    init_type
+                )
+            }
+            ctx.globals.push(&global.global_type);
+        }
+
+        // 要素セグメントはfuncrefの並びで、activeセグメントのオフセットはi32
+        for element in self.element_section.iter().flatten() {
+            if let ElementMode::Active { ref offset, .. } = element.mode {
+                let offset_type = ctx.const_expr_type(offset, import_globals)?;
+                if offset_type != I32 {
+                    bail!("type mismatch in element offset: found {:?}", offset_type)
+                }
+            }
+            for expr in element.init.iter() {
+                let elem_type = ctx.const_expr_type(expr, import_globals)?;
+                if elem_type != FuncRef {
+                    bail!("type mismatch in element: found {:?}", elem_type)
+                }
+            }
+        }
+
+        let bodies = self.code_section.as_deref().unwrap_or_default();
+        if func_type_idxs.len() != bodies.len() {
+            bail!("function and code section have inconsistent lengths")
+        }
+
+        for (i, body) in bodies.iter().enumerate() {
+            let idx = import_count + i;
+            let func_type = ctx.funcs[idx];
+
+            // ローカル変数は引数の後ろに続く
+            let mut locals = func_type.params.clone();
+            for local in body.locals.iter() {
+                for _ in 0..local.type_count {
+                    locals.push(local.value_type.clone());
+                }
+            }
+
+            let mut validator = FuncValidator {
+                ctx: &ctx,
+                locals,
+                vals: vec![],
+                ctrls: vec![],
+            };
+            validator
+                .validate(&func_type.results, &body.code)
+                .map_err(|e| anyhow!("invalid function {}: {}", idx, e))?;
+        }
+        Ok(())
+    }
+}
+
+impl Context<'_> {
+    // 初期化式の型を返す
+    fn const_expr_type(&self, expr: &ConstExpr, import_globals: usize) -> Result<ValueType> {
+        let value_type = match *expr {
+            ConstExpr::I32Const(_) => I32,
+            ConstExpr::I64Const(_) => I64,
+            ConstExpr::F32Const(_) => F32,
+            ConstExpr::F64Const(_) => F64,
+            ConstExpr::RefNull(ref ref_type) => {
+                if *ref_type != FuncRef {
+                    bail!("invalid reference type: {:?}", ref_type)
+                }
+                FuncRef
+            }
+            ConstExpr::RefFunc(idx) => {
+                if idx as usize >= self.funcs.len() {
+                    bail!("unknown function: {}", idx)
+                }
+                FuncRef
+            }
+            // global.getはインポートした不変のグローバル変数だけを参照できる
+            ConstExpr::GlobalGet(idx) => match self.globals[..import_globals].get(idx as usize) {
+                Some(global_type) if !global_type.mutable => global_type.value_type.clone(),
+                _ => bail!("invalid global initializer: global.get {}", idx),
+            },
+        };
+        Ok(value_type)
+    }
+}
+
+struct FuncValidator<'a> {
+    ctx: &'a Context<'a>,
+    locals: Vec<ValueType>,
+    vals: Vec<Option<ValueType>>, // Noneは型が不定(unreachableの後)
+    ctrls: Vec<CtrlFrame>,
+}
+
+impl FuncValidator<'_> {
+    fn validate(&mut self, results: &[ValueType], code: &[Instruction]) -> Result<()> {
+        // 関数本体は戻り値の型を持つブロックとして扱う
+        self.push_ctrl(CtrlKind::Block, vec![], results.to_vec());
+        for inst in code {
+            if self.ctrls.is_empty() {
+                bail!("instructions after the end of function")
+            }
+            self.validate_inst(inst)?;
+        }
+        if !self.ctrls.is_empty() {
+            bail!("missing end of function")
+        }
+        Ok(())
+    }
+
+    fn validate_inst(&mut self, inst: &Instruction) -> Result<()> {
+        match inst {
+            Instruction::Unreachable => self.set_unreachable()?,
+            Instruction::Nop => {}
+            Instruction::Block(block_type) => {
+                let (params, results) = self.block_type(block_type)?;
+                self.pop_vals(&params)?;
+                self.push_ctrl(CtrlKind::Block, params, results);
+            }
+            Instruction::Loop(block_type) => {
+                let (params, results) = self.block_type(block_type)?;
+                self.pop_vals(&params)?;
+                self.push_ctrl(CtrlKind::Loop, params, results);
+            }
+            Instruction::If(block_type) => {
+                let (params, results) = self.block_type(block_type)?;
+                self.pop_expect(I32)?;
+                self.pop_vals(&params)?;
+                self.push_ctrl(CtrlKind::If, params, results);
+            }
+            Instruction::Else => {
+                let frame = self.pop_ctrl()?;
+                if frame.kind != CtrlKind::If {
+                    bail!("else without matching if")
+                }
+                self.push_ctrl(CtrlKind::Else, frame.params, frame.results);
+            }
+            Instruction::End => {
+                let frame = self.pop_ctrl()?;
+                // elseのないifは、条件が偽のとき引数がそのまま戻り値になる
+                if frame.kind == CtrlKind::If && frame.params != frame.results {
+                    bail!("type mismatch: if without else must not change the stack")
+                }
+                self.push_vals(&frame.results);
+            }
+            Instruction::Br(depth) => {
+                let types = self.label_types(*depth)?;
+                self.pop_vals(&types)?;
+                self.set_unreachable()?;
+            }
+            Instruction::BrIf(depth) => {
+                self.pop_expect(I32)?;
+                let types = self.label_types(*depth)?;
+                self.pop_vals(&types)?;
+                self.push_vals(&types);
+            }
+            Instruction::BrTable(table) => {
+                self.pop_expect(I32)?;
+                let default_types = self.label_types(table.default)?;
+                for target in table.targets.iter() {
+                    let types = self.label_types(*target)?;
+                    if types.len() != default_types.len() {
+                        bail!("type mismatch: br_table targets have inconsistent arity")
+                    }
+                    self.pop_vals(&types)?;
+                    self.push_vals(&types);
+                }
+                self.pop_vals(&default_types)?;
+                self.set_unreachable()?;
+            }
+            Instruction::Call(idx) => {
+                let Some(func_type) = self.ctx.funcs.get(*idx as usize) else {
+                    bail!("unknown function: {}", idx)
+                };
+                self.op(&func_type.params, &func_type.results)?;
+            }
+            Instruction::CallIndirect {
+                type_idx,
+                table_idx,
+            } => {
+                self.check_table(*table_idx)?;
+                let Some(func_type) = self.ctx.types.get(*type_idx as usize) else {
+                    bail!("unknown type: {}", type_idx)
+                };
+                self.pop_expect(I32)?;
+                self.op(&func_type.params, &func_type.results)?;
+            }
+            Instruction::LocalGet(idx) => {
+                let value_type = self.local(*idx)?;
+                self.push_vals(&[value_type]);
+            }
+            Instruction::LocalSet(idx) => {
+                let value_type = self.local(*idx)?;
+                self.pop_expect(value_type)?;
+            }
+            Instruction::LocalTee(idx) => {
+                let value_type = self.local(*idx)?;
+                self.pop_expect(value_type.clone())?;
+                self.push_vals(&[value_type]);
+            }
+            Instruction::GlobalGet(idx) => {
+                let value_type = self.global(*idx)?.value_type.clone();
+                self.push_vals(&[value_type]);
+            }
+            Instruction::GlobalSet(idx) => {
+                let global_type = self.global(*idx)?;
+                if !global_type.mutable {
+                    bail!("global is immutable: {}", idx)
+                }
+                self.pop_expect(global_type.value_type.clone())?;
+            }
+            Instruction::TableGet(idx) => {
+                self.check_table(*idx)?;
+                self.op(&[I32], &[FuncRef])?
+            }
+            Instruction::TableSet(idx) => {
+                self.check_table(*idx)?;
+                self.op(&[I32, FuncRef], &[])?
+            }
+
+            Instruction::I32Load { align, .. } => self.load(*align, 32, I32)?,
+            Instruction::I64Load { align, .. } => self.load(*align, 64, I64)?,
+            Instruction::I32Load8S { align, .. } | Instruction::I32Load8U { align, .. } => {
+                self.load(*align, 8, I32)?
+            }
+            Instruction::I32Load16S { align, .. } | Instruction::I32Load16U { align, .. } => {
+                self.load(*align, 16, I32)?
+            }
+            Instruction::I64Load8S { align, .. } | Instruction::I64Load8U { align, .. } => {
+                self.load(*align, 8, I64)?
+            }
+            Instruction::I64Load16S { align, .. } | Instruction::I64Load16U { align, .. } => {
+                self.load(*align, 16, I64)?
+            }
+            Instruction::I64Load32S { align, .. } | Instruction::I64Load32U { align, .. } => {
+                self.load(*align, 32, I64)?
+            }
+            Instruction::I32Store { align, .. } => self.store(*align, 32, I32)?,
+            Instruction::I64Store { align, .. } => self.store(*align, 64, I64)?,
+            Instruction::I32Store8 { align, .. } => self.store(*align, 8, I32)?,
+            Instruction::I32Store16 { align, .. } => self.store(*align, 16, I32)?,
+            Instruction::I64Store8 { align, .. } => self.store(*align, 8, I64)?,
+            Instruction::I64Store16 { align, .. } => self.store(*align, 16, I64)?,
+            Instruction::I64Store32 { align, .. } => self.store(*align, 32, I64)?,
+
+            Instruction::I32Const(_) => self.push_vals(&[I32]),
+            Instruction::I64Const(_) => self.push_vals(&[I64]),
+            Instruction::F32Const(_) => self.push_vals(&[F32]),
+            Instruction::F64Const(_) => self.push_vals(&[F64]),
+
+            Instruction::I32Eqz => self.op(&[I32], &[I32])?,
+            Instruction::I64Eqz => self.op(&[I64], &[I32])?,
+            Instruction::I32Eq
+            | Instruction::I32Ne
+            | Instruction::I32LtS
+            | Instruction::I32LtU
+            | Instruction::I32GtS
+            | Instruction::I32GtU
+            | Instruction::I32LeS
+            | Instruction::I32LeU
+            | Instruction::I32GeS
+            | Instruction::I32GeU => self.op(&[I32, I32], &[I32])?,
+            Instruction::I64Eq
+            | Instruction::I64Ne
+            | Instruction::I64LtS
+            | Instruction::I64LtU
+            | Instruction::I64GtS
+            | Instruction::I64GtU
+            | Instruction::I64LeS
+            | Instruction::I64LeU
+            | Instruction::I64GeS
+            | Instruction::I64GeU => self.op(&[I64, I64], &[I32])?,
+            Instruction::F32Eq
+            | Instruction::F32Ne
+            | Instruction::F32Lt
+            | Instruction::F32Gt
+            | Instruction::F32Le
+            | Instruction::F32Ge => self.op(&[F32, F32], &[I32])?,
+            Instruction::F64Eq
+            | Instruction::F64Ne
+            | Instruction::F64Lt
+            | Instruction::F64Gt
+            | Instruction::F64Le
+            | Instruction::F64Ge => self.op(&[F64, F64], &[I32])?,
+
+            Instruction::I32Clz
+            | Instruction::I32Ctz
+            | Instruction::I32Popcnt
+            | Instruction::I32Extend8S
+            | Instruction::I32Extend16S => self.op(&[I32], &[I32])?,
+            Instruction::I32Add
+            | Instruction::I32Sub
+            | Instruction::I32Mul
+            | Instruction::I32DivS
+            | Instruction::I32DivU
+            | Instruction::I32RemS
+            | Instruction::I32RemU
+            | Instruction::I32And
+            | Instruction::I32Or
+            | Instruction::I32Xor
+            | Instruction::I32Shl
+            | Instruction::I32ShrS
+            | Instruction::I32ShrU
+            | Instruction::I32Rotl
+            | Instruction::I32Rotr => self.op(&[I32, I32], &[I32])?,
+            Instruction::I64Clz
+            | Instruction::I64Ctz
+            | Instruction::I64Popcnt
+            | Instruction::I64Extend8S
+            | Instruction::I64Extend16S
+            | Instruction::I64Extend32S => self.op(&[I64], &[I64])?,
+            Instruction::RefNull(ref_type) => self.push_vals(core::slice::from_ref(ref_type)),
+            Instruction::RefIsNull => self.op(&[FuncRef], &[I32])?,
+            Instruction::RefFunc(idx) => {
+                if *idx as usize >= self.ctx.funcs.len() {
+                    bail!("unknown function: {}", idx)
+                }
+                self.push_vals(&[FuncRef]);
+            }
+            Instruction::MemoryInit(idx) => {
+                self.check_memory()?;
+                self.check_data(*idx)?;
+                self.op(&[I32, I32, I32], &[])?
+            }
+            Instruction::DataDrop(idx) => self.check_data(*idx)?,
+            Instruction::MemoryCopy | Instruction::MemoryFill => {
+                self.check_memory()?;
+                self.op(&[I32, I32, I32], &[])?
+            }
+            Instruction::I64Add
+            | Instruction::I64Sub
+            | Instruction::I64Mul
+            | Instruction::I64DivS
+            | Instruction::I64DivU
+            | Instruction::I64RemS
+            | Instruction::I64RemU
+            | Instruction::I64And
+            | Instruction::I64Or
+            | Instruction::I64Xor
+            | Instruction::I64Shl
+            | Instruction::I64ShrS
+            | Instruction::I64ShrU
+            | Instruction::I64Rotl
+            | Instruction::I64Rotr => self.op(&[I64, I64], &[I64])?,
+            Instruction::F32Abs
+            | Instruction::F32Neg
+            | Instruction::F32Ceil
+            | Instruction::F32Floor
+            | Instruction::F32Trunc
+            | Instruction::F32Nearest
+            | Instruction::F32Sqrt => self.op(&[F32], &[F32])?,
+            Instruction::F32Add
+            | Instruction::F32Sub
+            | Instruction::F32Mul
+            | Instruction::F32Div => self.op(&[F32, F32], &[F32])?,
+            Instruction::F64Abs
+            | Instruction::F64Neg
+            | Instruction::F64Ceil
+            | Instruction::F64Floor
+            | Instruction::F64Trunc
+            | Instruction::F64Nearest
+            | Instruction::F64Sqrt => self.op(&[F64], &[F64])?,
+            Instruction::F64Add
+            | Instruction::F64Sub
+            | Instruction::F64Mul
+            | Instruction::F64Div => self.op(&[F64, F64], &[F64])?,
+
+            Instruction::I32WrapI64 => self.op(&[I64], &[I32])?,
+            Instruction::I32TruncF32S
+            | Instruction::I32TruncF32U
+            | Instruction::I32TruncSatF32S
+            | Instruction::I32TruncSatF32U => self.op(&[F32], &[I32])?,
+            Instruction::I32TruncF64S
+            | Instruction::I32TruncF64U
+            | Instruction::I32TruncSatF64S
+            | Instruction::I32TruncSatF64U => self.op(&[F64], &[I32])?,
+            Instruction::I64ExtendI32S | Instruction::I64ExtendI32U => self.op(&[I32], &[I64])?,
+            Instruction::I64TruncF32S
+            | Instruction::I64TruncF32U
+            | Instruction::I64TruncSatF32S
+            | Instruction::I64TruncSatF32U => self.op(&[F32], &[I64])?,
+            Instruction::I64TruncF64S
+            | Instruction::I64TruncF64U
+            | Instruction::I64TruncSatF64S
+            | Instruction::I64TruncSatF64U => self.op(&[F64], &[I64])?,
+            Instruction::F32ConvertI32S | Instruction::F32ConvertI32U => self.op(&[I32], &[F32])?,
+            Instruction::F32ConvertI64S | Instruction::F32ConvertI64U => self.op(&[I64], &[F32])?,
+            Instruction::F32DemoteF64 => self.op(&[F64], &[F32])?,
+            Instruction::F64ConvertI32S | Instruction::F64ConvertI32U => self.op(&[I32], &[F64])?,
+            Instruction::F64ConvertI64S | Instruction::F64ConvertI64U => self.op(&[I64], &[F64])?,
+            Instruction::F64PromoteF32 => self.op(&[F32], &[F64])?,
+            Instruction::I32ReinterpretF32 => self.op(&[F32], &[I32])?,
+            Instruction::I64ReinterpretF64 => self.op(&[F64], &[I64])?,
+            Instruction::F32ReinterpretI32 => self.op(&[I32], &[F32])?,
+            Instruction::F64ReinterpretI64 => self.op(&[I64], &[F64])?,
+        }
+        Ok(())
+    }
+
+    // 引数の型をpopし、戻り値の型をpushする
+    fn op(&mut self, params: &[ValueType], results: &[ValueType]) -> Result<()> {
+        self.pop_vals(params)?;
+        self.push_vals(results);
+        Ok(())
+    }
+
+    fn load(&mut self, align: u32, bits: u32, value_type: ValueType) -> Result<()> {
+        self.check_memarg(align, bits)?;
+        self.op(&[I32], &[value_type])
+    }
+
+    fn store(&mut self, align: u32, bits: u32, value_type: ValueType) -> Result<()> {
+        self.check_memarg(align, bits)?;
+        self.op(&[I32, value_type], &[])
+    }
+
+    fn check_table(&self, idx: u32) -> Result<()> {
+        if idx as usize >= self.ctx.tables {
+            bail!("unknown table: {}", idx)
+        }
+        Ok(())
+    }
+
+    fn check_memory(&self) -> Result<()> {
+        if self.ctx.memories == 0 {
+            bail!("unknown memory: 0")
+        }
+        Ok(())
+    }
+
+    fn check_data(&self, idx: u32) -> Result<()> {
+        if idx as usize >= self.ctx.datas {
+            bail!("unknown data segment: {}", idx)
+        }
+        Ok(())
+    }
+
+    // アライメントはアクセスするバイト数を超えてはいけない
+    fn check_memarg(&self, align: u32, bits: u32) -> Result<()> {
+        self.check_memory()?;
+        if align >= 32 || (1 << align) > bits / 8 {
+            bail!("alignment must not be larger than natural: {}", align)
+        }
+        Ok(())
+    }
+
+    fn local(&self, idx: u32) -> Result<ValueType> {
+        let Some(value_type) = self.locals.get(idx as usize) else {
+            bail!("unknown local: {}", idx)
+        };
+        Ok(value_type.clone())
+    }
+
+    fn global(&self, idx: u32) -> Result<&GlobalType> {
+        let Some(global_type) = self.ctx.globals.get(idx as usize) else {
+            bail!("unknown global: {}", idx)
+        };
+        Ok(global_type)
+    }
+
+    fn block_type(&self, block_type: &BlockType) -> Result<(Vec<ValueType>, Vec<ValueType>)> {
+        match block_type {
+            BlockType::Void => Ok((vec![], vec![])),
+            BlockType::Value(value_type) => Ok((vec![], vec![value_type.clone()])),
+            BlockType::TypeIndex(idx) => {
+                let Some(func_type) = self.ctx.types.get(*idx as usize) else {
+                    bail!("unknown type: {}", idx)
+                };
+                Ok((func_type.params.clone(), func_type.results.clone()))
+            }
+        }
+    }
+
+    // 分岐先のラベルが受け取る値の型(loopは引数、それ以外は戻り値)
+    fn label_types(&self, depth: u32) -> Result<Vec<ValueType>> {
+        let Some(idx) = self.ctrls.len().checked_sub(depth as usize + 1) else {
+            bail!("unknown label: {}", depth)
+        };
+        let frame = &self.ctrls[idx];
+        match frame.kind {
+            CtrlKind::Loop => Ok(frame.params.clone()),
+            _ => Ok(frame.results.clone()),
+        }
+    }
+
+    fn push_vals(&mut self, types: &[ValueType]) {
+        self.vals.extend(types.iter().cloned().map(Some));
+    }
+
+    fn pop_val(&mut self) -> Result<Option<ValueType>> {
+        let Some(frame) = self.ctrls.last() else {
+            bail!("no control frame")
+        };
+        if self.vals.len() == frame.height {
+            // unreachableの後は任意の型の値をpopできる
+            if frame.unreachable {
+                return Ok(None);
+            }
+            bail!("type mismatch: value stack underflow")
+        }
+        Ok(self.vals.pop().flatten())
+    }
+
+    fn pop_expect(&mut self, expect: ValueType) -> Result<()> {
+        match self.pop_val()? {
+            Some(actual) if actual != expect => {
+                bail!("type mismatch: expected {:?}, found {:?}", expect, actual)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn pop_vals(&mut self, types: &[ValueType]) -> Result<()> {
+        for value_type in types.iter().rev() {
+            self.pop_expect(value_type.clone())?;
+        }
+        Ok(())
+    }
+
+    fn push_ctrl(&mut self, kind: CtrlKind, params: Vec<ValueType>, results: Vec<ValueType>) {
+        // 引数はブロックの中に積まれる
+        let height = self.vals.len();
+        self.push_vals(&params);
+        self.ctrls.push(CtrlFrame {
+            kind,
+            params,
+            results,
+            height,
+            unreachable: false,
+        });
+    }
+
+    fn pop_ctrl(&mut self) -> Result<CtrlFrame> {
+        let Some(frame) = self.ctrls.last() else {
+            bail!("unexpected end")
+        };
+        let results = frame.results.clone();
+        let height = frame.height;
+        self.pop_vals(&results)?;
+        if self.vals.len() != height {
+            bail!("type mismatch: values remaining on the stack at the end of block")
+        }
+        let Some(frame) = self.ctrls.pop() else {
+            bail!("unexpected end")
+        };
+        Ok(frame)
+    }
+
+    fn set_unreachable(&mut self) -> Result<()> {
+        let Some(frame) = self.ctrls.last_mut() else {
+            bail!("no control frame")
+        };
+        self.vals.truncate(frame.height);
+        frame.unreachable = true;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::binary::module::Module;
+    use alloc::{string::ToString, vec};
+    use anyhow::Result;
+
+    fn validate(wat: &str) -> Result<()> {
+        let wasm = wat::parse_str(wat)?;
+        Module::new(&wasm)?.validate()
+    }
+
+    #[test]
+    fn validate_valid_funcs() -> Result<()> {
+        validate(
+            "(module (func (param i32 i32) (result i32) (i32.add (local.get 0) (local.get 1))))",
+        )?;
+        validate("(module (func (result i32) (block (result i32) (br 0 (i32.const 1)))))")?;
+        validate("(module (func (result i64) unreachable))")?;
+        validate(
+            "(module (func (param i32) (result i32) (if (result i32) (local.get 0) (then (i32.const 1)) (else (i32.const 2)))))",
+        )?;
+        Ok(())
+    }
+
+    #[test]
+    fn validate_type_mismatch() -> Result<()> {
+        let err = validate("(module (func (result i64) (i64.add (i32.const 1) (i32.const 2))))")
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid function 0: type mismatch: expected I64, found I32"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn validate_invalid_funcs() -> Result<()> {
+        let tests =
+            vec![
+            ("(module (func (result i32)))", "value stack underflow"),
+            ("(module (func (i32.const 1)))", "values remaining"),
+            (
+                "(module (func (local.set 0 (i32.const 1))))",
+                "unknown local: 0",
+            ),
+            (
+                "(module (func (global.set 0 (i32.const 1))))",
+                "unknown global: 0",
+            ),
+            (
+                "(module (global i32 (i32.const 0)) (func (global.set 0 (i32.const 1))))",
+                "global is immutable",
+            ),
+            ("(module (func (call 1)))", "unknown function: 1"),
+            ("(module (func (br 1)))", "unknown label: 1"),
+            (
+                "(module (func (i32.store (i32.const 0) (i32.const 1))))",
+                "unknown memory",
+            ),
+            (
+                "(module (global (import \"env\" \"g\") (mut i32)) (global i32 (global.get 0)))",
+                "invalid global initializer: global.get 0",
+            ),
+            (
+                "(module (global i32 (f32.const 1)))",
+                "type mismatch in global initializer",
+            ),
+            ("(module (global funcref (ref.func 0)))", "unknown function: 0"),
+        ];
+        for (wat, want) in tests {
+            let wasm = wat::parse_str(wat)?;
+            let err = Module::new(&wasm)?.validate().unwrap_err();
+            assert!(err.to_string().contains(want), "{wat}: {err}");
+        }
+        Ok(())
+    }
+}
@@ -1,3 +1,6 @@
+pub mod import;
 pub mod runtime;
 pub mod store;
+pub mod trap;
 pub mod value;
+pub mod wasi;
@@ -0,0 +1,61 @@
+use super::{runtime::Runtime, value::Value};
+use crate::binary::types::FuncType;
+use anyhow::Result;
+use std::{collections::HashMap, rc::Rc};
+
+// ホスト側(Rust)で実装した関数
+pub type HostFunc = Rc<dyn Fn(&mut Runtime, Vec<Value>) -> Result<Option<Value>>>;
+
+// (モジュール名, 名前)からホスト関数を引くためのレジストリ
+#[derive(Default, Clone)]
+pub struct Imports {
+    funcs: HashMap<(String, String), (FuncType, HostFunc)>, // シグネチャとホスト関数
+    globals: HashMap<(String, String), Value>,
+}
+
+impl Imports {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_func(
+        &mut self,
+        module: impl Into<String>,
+        field: impl Into<String>,
+        func_type: FuncType,
+        func: impl Fn(&mut Runtime, Vec<Value>) -> Result<Option<Value>> + 'static,
+    ) -> &mut Self {
+        self.funcs
+            .insert((module.into(), field.into()), (func_type, Rc::new(func)));
+        self
+    }
+
+    pub fn get_func(&self, module: &str, field: &str) -> Option<HostFunc> {
+        self.funcs
+            .get(&(module.to_string(), field.to_string()))
+            .map(|(_, func)| func.clone())
+    }
+
+    pub fn get_func_type(&self, module: &str, field: &str) -> Option<&FuncType> {
+        self.funcs
+            .get(&(module.to_string(), field.to_string()))
+            .map(|(func_type, _)| func_type)
+    }
+
+    // インポートするグローバル変数の値を登録する
+    pub fn add_global(
+        &mut self,
+        module: impl Into<String>,
+        field: impl Into<String>,
+        value: Value,
+    ) -> &mut Self {
+        self.globals.insert((module.into(), field.into()), value);
+        self
+    }
+
+    pub fn get_global(&self, module: &str, field: &str) -> Option<Value> {
+        self.globals
+            .get(&(module.to_string(), field.to_string()))
+            .copied()
+    }
+}
@@ -1,112 +1,1202 @@
 use super::{
-    store::{FuncInst, InternalFuncInst, Store},
+    import::Imports,
+    store::{
+        BlockAddress, DataInst, ExternalFuncInst, FuncInst, GlobalInst, InternalFuncInst,
+        MemoryInst, Store, TableInst,
+    },
+    trap::{Backtrace, Trap},
     value::Value,
 };
 use crate::binary::{
     instruction::Instruction,
-    module::Module,
-    types::{ExportDesc, ValueType},
+    module::{read_wasm_file, Module},
+    types::{BlockType, ExportDesc, FuncType, ImportDesc, ValueType},
 };
 use anyhow::{anyhow, bail, Result};
+use std::{path::Path, rc::Rc};
 
-#[derive(Default)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LabelKind {
+    Block,
+    Loop,
+    If,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Label {
+    pub kind: LabelKind,
+    pub pc: usize,    // 分岐先のプログラムカウンタ(blockはend、loopは先頭)
+    pub sp: usize,    // ブロック開始時のスタックポインタ
+    pub arity: usize, // 分岐時に残す値の個数(blockは戻り値、loopは引数)
+}
+
+#[derive(Default, Clone)]
 pub struct Frame {
-    pub pc: isize,               // プログラムカウンタ
-    pub sp: usize,               // スタックポインタ
-    pub insts: Vec<Instruction>, // 命令列
-    pub arity: usize,            // 戻り値の個数
-    pub locals: Vec<Value>,      // ローカル変数
+    pub func_idx: u32,                           // 実行中の関数のインデックス
+    pub pc: isize,                               // プログラムカウンタ
+    pub sp: usize,                               // スタックポインタ
+    pub insts: Rc<[Instruction]>,                // 命令列
+    pub block_addrs: Rc<[Option<BlockAddress>]>, // ブロックのelseとendの位置
+    pub arity: usize,                            // 戻り値の個数
+    pub locals: Vec<Value>,                      // ローカル変数
+    pub labels: Vec<Label>,                      // ラベルスタック
 }
 
-#[derive(Default)]
+// ある時点のRuntimeの状態(restoreで巻き戻せる)
+#[derive(Clone)]
+pub struct RuntimeSnapshot {
+    stack: Vec<Value>,
+    call_stack: Vec<Frame>,
+    globals: Vec<GlobalInst>,
+    table: TableInst,
+    memory: MemoryInst,
+    datas: Vec<DataInst>,
+}
+
+// 呼び出しの深さの上限(デフォルト)
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 1024;
+
 pub struct Runtime {
     pub store: Store,
     pub stack: Vec<Value>,
     pub call_stack: Vec<Frame>,
+    pub imports: Imports,
+    pub max_call_depth: usize, // これを超えて関数を呼び出すとトラップする
+    // trueにすると、トラップした呼び出しで書き換えたグローバル変数、テーブル、
+    // 線形メモリを呼び出し前の状態に戻す(デフォルトはfalseで、変更はそのまま残る)
+    pub rollback_on_trap: bool,
+}
+
+impl Default for Runtime {
+    fn default() -> Self {
+        Self {
+            store: Store::default(),
+            stack: vec![],
+            call_stack: vec![],
+            imports: Imports::default(),
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+            rollback_on_trap: false,
+        }
+    }
+}
+
+// ホスト関数を登録してからインスタンス化するためのビルダー
+#[derive(Default)]
+pub struct RuntimeBuilder {
+    imports: Imports,
+}
+
+impl RuntimeBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn define(
+        mut self,
+        module: impl Into<String>,
+        field: impl Into<String>,
+        func_type: FuncType,
+        func: impl Fn(&mut Runtime, Vec<Value>) -> Result<Option<Value>> + 'static,
+    ) -> Self {
+        self.imports.add_func(module, field, func_type, func);
+        self
+    }
+
+    pub fn define_global(
+        mut self,
+        module: impl Into<String>,
+        field: impl Into<String>,
+        value: Value,
+    ) -> Self {
+        self.imports.add_global(module, field, value);
+        self
+    }
+
+    pub fn instantiate(self, wasm: impl AsRef<[u8]>) -> Result<Runtime> {
+        Runtime::instantiate_with_imports(wasm, self.imports)
+    }
 }
 
 impl Runtime {
     pub fn instantiate(wasm: impl AsRef<[u8]>) -> Result<Self> {
+        Self::instantiate_with_imports(wasm, Imports::default())
+    }
+
+    pub fn instantiate_file(path: impl AsRef<Path>) -> Result<Self> {
+        Self::instantiate(read_wasm_file(path)?)
+    }
+
+    pub fn builder() -> RuntimeBuilder {
+        RuntimeBuilder::new()
+    }
+
+    // インポートする関数を登録してインスタンス化する
+    pub fn instantiate_with_imports(wasm: impl AsRef<[u8]>, imports: Imports) -> Result<Self> {
         let module = Module::new(wasm.as_ref())?;
-        let store = Store::new(module)?;
-        Ok(Self {
+        Self::instantiate_module(module, imports)
+    }
+
+    fn instantiate_module(module: Module, imports: Imports) -> Result<Self> {
+        // 実行前に関数本体を型検査する
+        module.validate()?;
+        resolve_imports(&module, &imports)?;
+        let start = module.start_section;
+        let store = Store::with_imports(module, &imports)?;
+        let mut runtime = Self {
             store,
+            imports,
             ..Default::default()
-        })
+        };
+
+        // start関数があればインスタンス化時に一度だけ実行する
+        if let Some(idx) = start {
+            runtime.call_by_func_index(idx as usize, vec![])?;
+        }
+        Ok(runtime)
     }
 
     fn execute(&mut self) -> Result<()> {
-        loop {
+        // ホスト関数から再帰的に呼ばれた場合に、呼び出し元のフレームまで実行しないようにする
+        let depth = self.call_stack.len();
+        while self.call_stack.len() >= depth {
             let Some(frame) = self.call_stack.last_mut() else {
                 break;
             };
-
             frame.pc += 1;
             let Some(inst) = frame.insts.get(frame.pc as usize) else {
                 break;
             };
 
             match inst {
-                Instruction::End => {
-                    // コールスタックからフレームをpopし、
-                    // フレームの情報からspとarityを取り出し、スタックを戻す
-                    let Some(frame) = self.call_stack.pop() else {
-                        bail!("not found frame");
+                Instruction::Unreachable => bail!(Trap::Unreachable),
+                Instruction::Nop => {}
+                Instruction::Block(block_type) => {
+                    let (params, arity) = block_arity(block_type, &self.store.module.func_types)?;
+                    let Some(sp) = self.stack.len().checked_sub(params) else {
+                        bail!(Trap::StackUnderflow);
+                    };
+                    let BlockAddress { end_pc: pc, .. } = block_address(frame)?;
+                    frame.labels.push(Label {
+                        kind: LabelKind::Block,
+                        pc,
+                        sp,
+                        arity,
+                    });
+                }
+                Instruction::Loop(block_type) => {
+                    let (params, _) = block_arity(block_type, &self.store.module.func_types)?;
+                    let Some(sp) = self.stack.len().checked_sub(params) else {
+                        bail!(Trap::StackUnderflow);
+                    };
+                    frame.labels.push(Label {
+                        kind: LabelKind::Loop,
+                        pc: frame.pc as usize,
+                        sp,
+                        arity: params,
+                    });
+                }
+                Instruction::If(block_type) => {
+                    let cond = pop::<i32>(&mut self.stack)?;
+                    let (params, arity) = block_arity(block_type, &self.store.module.func_types)?;
+                    let Some(sp) = self.stack.len().checked_sub(params) else {
+                        bail!(Trap::StackUnderflow);
+                    };
+                    let BlockAddress { else_pc, end_pc } = block_address(frame)?;
+                    if cond == 0 {
+                        // elseがあればelseの次から、なければendから実行する
+                        frame.pc = match else_pc {
+                            Some(pc) => pc as isize,
+                            None => end_pc as isize - 1,
+                        };
+                    }
+                    frame.labels.push(Label {
+                        kind: LabelKind::If,
+                        pc: end_pc,
+                        sp,
+                        arity,
+                    });
+                }
+                Instruction::Else => {
+                    // thenの実行が終わったので、elseを飛ばしてendから実行する
+                    let Some(label) = frame.labels.last() else {
+                        bail!("not found label");
+                    };
+                    frame.pc = label.pc as isize - 1;
+                }
+                Instruction::End => match frame.labels.pop() {
+                    // ブロックの終わりならラベルをpopし、ブロック開始時のスタックに戻す
+                    Some(Label {
+                        kind, sp, arity, ..
+                    }) => {
+                        // loopのarityは引数の個数なので、ブロック内に残った値をそのまま残す
+                        let arity = match kind {
+                            LabelKind::Block | LabelKind::If => arity,
+                            LabelKind::Loop => self.stack.len().saturating_sub(sp),
+                        };
+                        stack_unwind(&mut self.stack, sp, arity)?
+                    }
+                    None => {
+                        // コールスタックからフレームをpopし、
+                        // フレームの情報からspとarityを取り出し、スタックを戻す
+                        let Some(frame) = self.call_stack.pop() else {
+                            bail!("not found frame");
+                        };
+                        let Frame { sp, arity, .. } = frame;
+                        stack_unwind(&mut self.stack, sp, arity)?;
+                    }
+                },
+                Instruction::Br(depth) => {
+                    let depth = *depth;
+                    self.branch(depth)?;
+                }
+                Instruction::BrIf(depth) => {
+                    let depth = *depth;
+                    let cond = pop::<i32>(&mut self.stack)?;
+                    if cond != 0 {
+                        self.branch(depth)?;
+                    }
+                }
+                Instruction::BrTable(table) => {
+                    let idx = pop::<i32>(&mut self.stack)? as u32 as usize;
+                    // 範囲外のインデックスはdefaultに分岐する
+                    let depth = *table.targets.get(idx).unwrap_or(&table.default);
+                    self.branch(depth)?;
+                }
+                Instruction::Call(idx) => {
+                    let idx = *idx;
+                    let Some(func_inst) = self.store.funcs.get(idx as usize) else {
+                        bail!("not found func");
                     };
-                    let Frame { sp, arity, .. } = frame;
-                    stack_unwind(&mut self.stack, sp, arity)?;
+                    // 呼び出し先のフレームをpushし、そのまま実行ループを続ける
+                    match func_inst.clone() {
+                        FuncInst::Internal(func) => self.push_frame(idx, &func)?,
+                        FuncInst::External(func) => self.invoke_external(func)?,
+                    }
+                }
+                Instruction::CallIndirect {
+                    type_idx,
+                    table_idx,
+                } => {
+                    // テーブルは1つまで
+                    if *table_idx != 0 {
+                        bail!("not found table");
+                    }
+                    let elem_idx = pop::<i32>(&mut self.stack)? as u32 as usize;
+                    let Some(&Some(func_idx)) = self.store.table.elems.get(elem_idx) else {
+                        bail!(Trap::UndefinedElement);
+                    };
+                    let Some(func_inst) = self.store.funcs.get(func_idx as usize) else {
+                        bail!("not found func");
+                    };
+                    let Some(func_type) = self.store.module.func_types.get(*type_idx as usize)
+                    else {
+                        bail!("not found func type");
+                    };
+                    // 呼び出し先の関数のシグネチャが期待する型と一致するか確認
+                    if !func_inst.func_type().matches(func_type) {
+                        bail!(Trap::IndirectCallTypeMismatch);
+                    }
+                    match func_inst.clone() {
+                        FuncInst::Internal(func) => self.push_frame(func_idx, &func)?,
+                        FuncInst::External(func) => self.invoke_external(func)?,
+                    }
                 }
                 Instruction::LocalGet(idx) => {
                     let Some(value) = frame.locals.get(*idx as usize) else {
-                        bail!("not found local");
+                        bail!(Trap::LocalOutOfBounds {
+                            idx: *idx,
+                            len: frame.locals.len()
+                        });
                     };
                     self.stack.push(*value);
                 }
+                Instruction::LocalSet(idx) => {
+                    let Some(value) = self.stack.pop() else {
+                        bail!(Trap::StackUnderflow);
+                    };
+                    let Some(local) = frame.locals.get_mut(*idx as usize) else {
+                        bail!(Trap::LocalOutOfBounds {
+                            idx: *idx,
+                            len: frame.locals.len()
+                        });
+                    };
+                    *local = value;
+                }
+                Instruction::LocalTee(idx) => {
+                    // スタックの値はpopせずに残しておく
+                    let Some(value) = self.stack.last() else {
+                        bail!(Trap::StackUnderflow);
+                    };
+                    let Some(local) = frame.locals.get_mut(*idx as usize) else {
+                        bail!(Trap::LocalOutOfBounds {
+                            idx: *idx,
+                            len: frame.locals.len()
+                        });
+                    };
+                    *local = *value;
+                }
+                Instruction::GlobalGet(idx) => {
+                    let Some(global) = self.store.globals.get(*idx as usize) else {
+                        bail!("not found global");
+                    };
+                    self.stack.push(global.value);
+                }
+                Instruction::GlobalSet(idx) => {
+                    let Some(global) = self.store.globals.get_mut(*idx as usize) else {
+                        bail!("not found global");
+                    };
+                    if !global.mutable {
+                        bail!("global is immutable");
+                    }
+                    let Some(value) = self.stack.pop() else {
+                        bail!(Trap::StackUnderflow);
+                    };
+                    global.value = value;
+                }
+                // テーブルは1つまで
+                Instruction::TableGet(idx) => {
+                    if *idx != 0 {
+                        bail!("not found table");
+                    }
+                    let elem_idx = pop::<i32>(&mut self.stack)? as u32 as usize;
+                    let Some(elem) = self.store.table.elems.get(elem_idx) else {
+                        bail!(Trap::OutOfBoundsTableAccess);
+                    };
+                    self.stack.push(Value::FuncRef(*elem));
+                }
+                Instruction::TableSet(idx) => {
+                    if *idx != 0 {
+                        bail!("not found table");
+                    }
+                    let value = pop::<Option<u32>>(&mut self.stack)?;
+                    let elem_idx = pop::<i32>(&mut self.stack)? as u32 as usize;
+                    let Some(elem) = self.store.table.elems.get_mut(elem_idx) else {
+                        bail!(Trap::OutOfBoundsTableAccess);
+                    };
+                    *elem = value;
+                }
+                Instruction::I32Load { offset, .. } => {
+                    let addr = pop::<i32>(&mut self.stack)?;
+                    let bytes = read_memory(&self.store.memory, addr, *offset)?;
+                    self.stack.push(Value::I32(i32::from_le_bytes(bytes)));
+                }
+                Instruction::I64Load { offset, .. } => {
+                    let addr = pop::<i32>(&mut self.stack)?;
+                    let bytes = read_memory(&self.store.memory, addr, *offset)?;
+                    self.stack.push(Value::I64(i64::from_le_bytes(bytes)));
+                }
+                Instruction::I32Load8S { offset, .. } => {
+                    let addr = pop::<i32>(&mut self.stack)?;
+                    let bytes = read_memory(&self.store.memory, addr, *offset)?;
+                    self.stack.push(Value::I32(i8::from_le_bytes(bytes) as i32));
+                }
+                Instruction::I32Load8U { offset, .. } => {
+                    let addr = pop::<i32>(&mut self.stack)?;
+                    let bytes = read_memory(&self.store.memory, addr, *offset)?;
+                    self.stack.push(Value::I32(u8::from_le_bytes(bytes) as i32));
+                }
+                Instruction::I32Load16S { offset, .. } => {
+                    let addr = pop::<i32>(&mut self.stack)?;
+                    let bytes = read_memory(&self.store.memory, addr, *offset)?;
+                    self.stack
+                        .push(Value::I32(i16::from_le_bytes(bytes) as i32));
+                }
+                Instruction::I32Load16U { offset, .. } => {
+                    let addr = pop::<i32>(&mut self.stack)?;
+                    let bytes = read_memory(&self.store.memory, addr, *offset)?;
+                    self.stack
+                        .push(Value::I32(u16::from_le_bytes(bytes) as i32));
+                }
+                Instruction::I64Load8S { offset, .. } => {
+                    let addr = pop::<i32>(&mut self.stack)?;
+                    let bytes = read_memory(&self.store.memory, addr, *offset)?;
+                    self.stack.push(Value::I64(i8::from_le_bytes(bytes) as i64));
+                }
+                Instruction::I64Load8U { offset, .. } => {
+                    let addr = pop::<i32>(&mut self.stack)?;
+                    let bytes = read_memory(&self.store.memory, addr, *offset)?;
+                    self.stack.push(Value::I64(u8::from_le_bytes(bytes) as i64));
+                }
+                Instruction::I64Load16S { offset, .. } => {
+                    let addr = pop::<i32>(&mut self.stack)?;
+                    let bytes = read_memory(&self.store.memory, addr, *offset)?;
+                    self.stack
+                        .push(Value::I64(i16::from_le_bytes(bytes) as i64));
+                }
+                Instruction::I64Load16U { offset, .. } => {
+                    let addr = pop::<i32>(&mut self.stack)?;
+                    let bytes = read_memory(&self.store.memory, addr, *offset)?;
+                    self.stack
+                        .push(Value::I64(u16::from_le_bytes(bytes) as i64));
+                }
+                Instruction::I64Load32S { offset, .. } => {
+                    let addr = pop::<i32>(&mut self.stack)?;
+                    let bytes = read_memory(&self.store.memory, addr, *offset)?;
+                    self.stack
+                        .push(Value::I64(i32::from_le_bytes(bytes) as i64));
+                }
+                Instruction::I64Load32U { offset, .. } => {
+                    let addr = pop::<i32>(&mut self.stack)?;
+                    let bytes = read_memory(&self.store.memory, addr, *offset)?;
+                    self.stack
+                        .push(Value::I64(u32::from_le_bytes(bytes) as i64));
+                }
+                Instruction::I32Store { offset, .. } => {
+                    let value = pop::<i32>(&mut self.stack)?;
+                    let addr = pop::<i32>(&mut self.stack)?;
+                    write_memory(&mut self.store.memory, addr, *offset, &value.to_le_bytes())?;
+                }
+                Instruction::I64Store { offset, .. } => {
+                    let value = pop::<i64>(&mut self.stack)?;
+                    let addr = pop::<i32>(&mut self.stack)?;
+                    write_memory(&mut self.store.memory, addr, *offset, &value.to_le_bytes())?;
+                }
+                Instruction::I32Store8 { offset, .. } => {
+                    // 下位1バイトだけを書き込む
+                    let value = pop::<i32>(&mut self.stack)? as u8;
+                    let addr = pop::<i32>(&mut self.stack)?;
+                    write_memory(&mut self.store.memory, addr, *offset, &value.to_le_bytes())?;
+                }
+                Instruction::I32Store16 { offset, .. } => {
+                    // 下位2バイトだけを書き込む
+                    let value = pop::<i32>(&mut self.stack)? as u16;
+                    let addr = pop::<i32>(&mut self.stack)?;
+                    write_memory(&mut self.store.memory, addr, *offset, &value.to_le_bytes())?;
+                }
+                Instruction::I64Store8 { offset, .. } => {
+                    let value = pop::<i64>(&mut self.stack)? as u8;
+                    let addr = pop::<i32>(&mut self.stack)?;
+                    write_memory(&mut self.store.memory, addr, *offset, &value.to_le_bytes())?;
+                }
+                Instruction::I64Store16 { offset, .. } => {
+                    let value = pop::<i64>(&mut self.stack)? as u16;
+                    let addr = pop::<i32>(&mut self.stack)?;
+                    write_memory(&mut self.store.memory, addr, *offset, &value.to_le_bytes())?;
+                }
+                Instruction::I64Store32 { offset, .. } => {
+                    let value = pop::<i64>(&mut self.stack)? as u32;
+                    let addr = pop::<i32>(&mut self.stack)?;
+                    write_memory(&mut self.store.memory, addr, *offset, &value.to_le_bytes())?;
+                }
+                Instruction::I32Const(val) => self.stack.push(Value::I32(*val)),
                 Instruction::I64Const(val) => self.stack.push(Value::I64(*val)),
-                Instruction::I32Add => {
-                    let (Some(rhs), Some(lhs)) = (self.stack.pop(), self.stack.pop()) else {
-                        bail!("not found any value in the stack");
+                Instruction::F32Const(val) => self.stack.push(Value::F32(*val)),
+                Instruction::F64Const(val) => self.stack.push(Value::F64(*val)),
+                Instruction::I32Eqz => {
+                    unary_op(&mut self.stack, |value: i32| Ok((value == 0).into()))?
+                }
+                Instruction::I32Eq => {
+                    binary_op(
+                        &mut self.stack,
+                        |lhs: i32, rhs: i32| Ok((lhs == rhs).into()),
+                    )?
+                }
+                Instruction::I32Ne => {
+                    binary_op(
+                        &mut self.stack,
+                        |lhs: i32, rhs: i32| Ok((lhs != rhs).into()),
+                    )?
+                }
+                Instruction::I32LtS => {
+                    binary_op(&mut self.stack, |lhs: i32, rhs: i32| Ok((lhs < rhs).into()))?
+                }
+                Instruction::I32LtU => binary_op(&mut self.stack, |lhs: i32, rhs: i32| {
+                    Ok(((lhs as u32) < (rhs as u32)).into())
+                })?,
+                Instruction::I32GtS => {
+                    binary_op(&mut self.stack, |lhs: i32, rhs: i32| Ok((lhs > rhs).into()))?
+                }
+                Instruction::I32GtU => binary_op(&mut self.stack, |lhs: i32, rhs: i32| {
+                    Ok(((lhs as u32) > (rhs as u32)).into())
+                })?,
+                Instruction::I32LeS => {
+                    binary_op(
+                        &mut self.stack,
+                        |lhs: i32, rhs: i32| Ok((lhs <= rhs).into()),
+                    )?
+                }
+                Instruction::I32LeU => binary_op(&mut self.stack, |lhs: i32, rhs: i32| {
+                    Ok(((lhs as u32) <= (rhs as u32)).into())
+                })?,
+                Instruction::I32GeS => {
+                    binary_op(
+                        &mut self.stack,
+                        |lhs: i32, rhs: i32| Ok((lhs >= rhs).into()),
+                    )?
+                }
+                Instruction::I32GeU => binary_op(&mut self.stack, |lhs: i32, rhs: i32| {
+                    Ok(((lhs as u32) >= (rhs as u32)).into())
+                })?,
+                Instruction::I64Eqz => {
+                    unary_op(&mut self.stack, |value: i64| Ok((value == 0).into()))?
+                }
+                Instruction::I64Eq => {
+                    binary_op(
+                        &mut self.stack,
+                        |lhs: i64, rhs: i64| Ok((lhs == rhs).into()),
+                    )?
+                }
+                Instruction::I64Ne => {
+                    binary_op(
+                        &mut self.stack,
+                        |lhs: i64, rhs: i64| Ok((lhs != rhs).into()),
+                    )?
+                }
+                Instruction::I64LtS => {
+                    binary_op(&mut self.stack, |lhs: i64, rhs: i64| Ok((lhs < rhs).into()))?
+                }
+                Instruction::I64LtU => binary_op(&mut self.stack, |lhs: i64, rhs: i64| {
+                    Ok(((lhs as u64) < (rhs as u64)).into())
+                })?,
+                Instruction::I64GtS => {
+                    binary_op(&mut self.stack, |lhs: i64, rhs: i64| Ok((lhs > rhs).into()))?
+                }
+                Instruction::I64GtU => binary_op(&mut self.stack, |lhs: i64, rhs: i64| {
+                    Ok(((lhs as u64) > (rhs as u64)).into())
+                })?,
+                Instruction::I64LeS => {
+                    binary_op(
+                        &mut self.stack,
+                        |lhs: i64, rhs: i64| Ok((lhs <= rhs).into()),
+                    )?
+                }
+                Instruction::I64LeU => binary_op(&mut self.stack, |lhs: i64, rhs: i64| {
+                    Ok(((lhs as u64) <= (rhs as u64)).into())
+                })?,
+                Instruction::I64GeS => {
+                    binary_op(
+                        &mut self.stack,
+                        |lhs: i64, rhs: i64| Ok((lhs >= rhs).into()),
+                    )?
+                }
+                Instruction::I64GeU => binary_op(&mut self.stack, |lhs: i64, rhs: i64| {
+                    Ok(((lhs as u64) >= (rhs as u64)).into())
+                })?,
+                // NaNとの比較はneのみtrueで、それ以外はすべてfalse(Rustの比較演算子と同じ)
+                Instruction::F32Eq => {
+                    binary_op(
+                        &mut self.stack,
+                        |lhs: f32, rhs: f32| Ok((lhs == rhs).into()),
+                    )?
+                }
+                Instruction::F32Ne => {
+                    binary_op(
+                        &mut self.stack,
+                        |lhs: f32, rhs: f32| Ok((lhs != rhs).into()),
+                    )?
+                }
+                Instruction::F32Lt => {
+                    binary_op(&mut self.stack, |lhs: f32, rhs: f32| Ok((lhs < rhs).into()))?
+                }
+                Instruction::F32Gt => {
+                    binary_op(&mut self.stack, |lhs: f32, rhs: f32| Ok((lhs > rhs).into()))?
+                }
+                Instruction::F32Le => {
+                    binary_op(
+                        &mut self.stack,
+                        |lhs: f32, rhs: f32| Ok((lhs <= rhs).into()),
+                    )?
+                }
+                Instruction::F32Ge => {
+                    binary_op(
+                        &mut self.stack,
+                        |lhs: f32, rhs: f32| Ok((lhs >= rhs).into()),
+                    )?
+                }
+                Instruction::F64Eq => {
+                    binary_op(
+                        &mut self.stack,
+                        |lhs: f64, rhs: f64| Ok((lhs == rhs).into()),
+                    )?
+                }
+                Instruction::F64Ne => {
+                    binary_op(
+                        &mut self.stack,
+                        |lhs: f64, rhs: f64| Ok((lhs != rhs).into()),
+                    )?
+                }
+                Instruction::F64Lt => {
+                    binary_op(&mut self.stack, |lhs: f64, rhs: f64| Ok((lhs < rhs).into()))?
+                }
+                Instruction::F64Gt => {
+                    binary_op(&mut self.stack, |lhs: f64, rhs: f64| Ok((lhs > rhs).into()))?
+                }
+                Instruction::F64Le => {
+                    binary_op(
+                        &mut self.stack,
+                        |lhs: f64, rhs: f64| Ok((lhs <= rhs).into()),
+                    )?
+                }
+                Instruction::F64Ge => {
+                    binary_op(
+                        &mut self.stack,
+                        |lhs: f64, rhs: f64| Ok((lhs >= rhs).into()),
+                    )?
+                }
+                // 下位32ビットを取り出す
+                Instruction::I32WrapI64 => {
+                    unary_op(&mut self.stack, |value: i64| Ok((value as i32).into()))?
+                }
+                Instruction::I64ExtendI32S => {
+                    unary_op(&mut self.stack, |value: i32| Ok((value as i64).into()))?
+                }
+                // u32を経由してゼロ拡張する
+                Instruction::I64ExtendI32U => unary_op(&mut self.stack, |value: i32| {
+                    Ok((value as u32 as i64).into())
+                })?,
+                Instruction::I32TruncF32S => unary_op(&mut self.stack, |value: f32| {
+                    let value = trunc_float(value as f64, I32_MIN, I32_MAX)?;
+                    Ok((value as i32).into())
+                })?,
+                Instruction::I32TruncF32U => unary_op(&mut self.stack, |value: f32| {
+                    let value = trunc_float(value as f64, 0.0, U32_MAX)?;
+                    Ok((value as u32 as i32).into())
+                })?,
+                Instruction::I32TruncF64S => unary_op(&mut self.stack, |value: f64| {
+                    let value = trunc_float(value, I32_MIN, I32_MAX)?;
+                    Ok((value as i32).into())
+                })?,
+                Instruction::I32TruncF64U => unary_op(&mut self.stack, |value: f64| {
+                    let value = trunc_float(value, 0.0, U32_MAX)?;
+                    Ok((value as u32 as i32).into())
+                })?,
+                Instruction::I64TruncF32S => unary_op(&mut self.stack, |value: f32| {
+                    let value = trunc_float(value as f64, I64_MIN, I64_MAX)?;
+                    Ok((value as i64).into())
+                })?,
+                Instruction::I64TruncF32U => unary_op(&mut self.stack, |value: f32| {
+                    let value = trunc_float(value as f64, 0.0, U64_MAX)?;
+                    Ok((value as u64 as i64).into())
+                })?,
+                Instruction::I64TruncF64S => unary_op(&mut self.stack, |value: f64| {
+                    let value = trunc_float(value, I64_MIN, I64_MAX)?;
+                    Ok((value as i64).into())
+                })?,
+                Instruction::I64TruncF64U => unary_op(&mut self.stack, |value: f64| {
+                    let value = trunc_float(value, 0.0, U64_MAX)?;
+                    Ok((value as u64 as i64).into())
+                })?,
+                // asによる変換は範囲外を最小値/最大値に、NaNを0に丸めるのでトラップしない
+                Instruction::I32TruncSatF32S => {
+                    unary_op(&mut self.stack, |value: f32| Ok((value as i32).into()))?
+                }
+                Instruction::I32TruncSatF32U => unary_op(&mut self.stack, |value: f32| {
+                    Ok((value as u32 as i32).into())
+                })?,
+                Instruction::I32TruncSatF64S => {
+                    unary_op(&mut self.stack, |value: f64| Ok((value as i32).into()))?
+                }
+                Instruction::I32TruncSatF64U => unary_op(&mut self.stack, |value: f64| {
+                    Ok((value as u32 as i32).into())
+                })?,
+                Instruction::I64TruncSatF32S => {
+                    unary_op(&mut self.stack, |value: f32| Ok((value as i64).into()))?
+                }
+                Instruction::I64TruncSatF32U => unary_op(&mut self.stack, |value: f32| {
+                    Ok((value as u64 as i64).into())
+                })?,
+                Instruction::I64TruncSatF64S => {
+                    unary_op(&mut self.stack, |value: f64| Ok((value as i64).into()))?
+                }
+                Instruction::I64TruncSatF64U => unary_op(&mut self.stack, |value: f64| {
+                    Ok((value as u64 as i64).into())
+                })?,
+                Instruction::F32ConvertI32S => {
+                    unary_op(&mut self.stack, |value: i32| Ok((value as f32).into()))?
+                }
+                // _uは符号なし整数として解釈してから変換する
+                Instruction::F32ConvertI32U => unary_op(&mut self.stack, |value: i32| {
+                    Ok((value as u32 as f32).into())
+                })?,
+                Instruction::F32ConvertI64S => {
+                    unary_op(&mut self.stack, |value: i64| Ok((value as f32).into()))?
+                }
+                Instruction::F32ConvertI64U => unary_op(&mut self.stack, |value: i64| {
+                    Ok((value as u64 as f32).into())
+                })?,
+                Instruction::F64ConvertI32S => {
+                    unary_op(&mut self.stack, |value: i32| Ok((value as f64).into()))?
+                }
+                Instruction::F64ConvertI32U => unary_op(&mut self.stack, |value: i32| {
+                    Ok((value as u32 as f64).into())
+                })?,
+                Instruction::F64ConvertI64S => {
+                    unary_op(&mut self.stack, |value: i64| Ok((value as f64).into()))?
+                }
+                Instruction::F64ConvertI64U => unary_op(&mut self.stack, |value: i64| {
+                    Ok((value as u64 as f64).into())
+                })?,
+                Instruction::F32DemoteF64 => {
+                    unary_op(&mut self.stack, |value: f64| Ok((value as f32).into()))?
+                }
+                // asによる変換はNaNをNaNのまま保つ
+                Instruction::F64PromoteF32 => {
+                    unary_op(&mut self.stack, |value: f32| Ok((value as f64).into()))?
+                }
+                // ビット列をそのまま別の型として解釈する
+                Instruction::I32ReinterpretF32 => unary_op(&mut self.stack, |value: f32| {
+                    Ok((value.to_bits() as i32).into())
+                })?,
+                Instruction::I64ReinterpretF64 => unary_op(&mut self.stack, |value: f64| {
+                    Ok((value.to_bits() as i64).into())
+                })?,
+                Instruction::F32ReinterpretI32 => unary_op(&mut self.stack, |value: i32| {
+                    Ok(f32::from_bits(value as u32).into())
+                })?,
+                Instruction::F64ReinterpretI64 => unary_op(&mut self.stack, |value: i64| {
+                    Ok(f64::from_bits(value as u64).into())
+                })?,
+                // 下位Nビットを符号拡張する
+                Instruction::I32Extend8S => {
+                    unary_op(
+                        &mut self.stack,
+                        |value: i32| Ok((value as i8 as i32).into()),
+                    )?
+                }
+                Instruction::I32Extend16S => unary_op(&mut self.stack, |value: i32| {
+                    Ok((value as i16 as i32).into())
+                })?,
+                Instruction::I64Extend8S => {
+                    unary_op(
+                        &mut self.stack,
+                        |value: i64| Ok((value as i8 as i64).into()),
+                    )?
+                }
+                Instruction::I64Extend16S => unary_op(&mut self.stack, |value: i64| {
+                    Ok((value as i16 as i64).into())
+                })?,
+                Instruction::I64Extend32S => unary_op(&mut self.stack, |value: i64| {
+                    Ok((value as i32 as i64).into())
+                })?,
+                Instruction::RefNull(_) => self.stack.push(Value::FuncRef(None)),
+                Instruction::RefIsNull => {
+                    let value = pop::<Option<u32>>(&mut self.stack)?;
+                    self.stack.push(value.is_none().into());
+                }
+                Instruction::RefFunc(idx) => self.stack.push(Value::FuncRef(Some(*idx))),
+                Instruction::MemoryInit(idx) => {
+                    let len = pop::<i32>(&mut self.stack)? as u32 as usize;
+                    let src = pop::<i32>(&mut self.stack)? as u32 as usize;
+                    let dst = pop::<i32>(&mut self.stack)? as u32 as usize;
+                    let Some(data) = self.store.datas.get(*idx as usize) else {
+                        bail!("not found data segment");
+                    };
+                    // drop済みのセグメントは長さ0として扱う
+                    let Some(src) = data.data.get(src..src + len) else {
+                        bail!(Trap::OutOfBoundsMemoryAccess);
                     };
-                    let result = lhs + rhs;
-                    self.stack.push(result);
+                    let Some(dst) = self.store.memory.data.get_mut(dst..dst + len) else {
+                        bail!(Trap::OutOfBoundsMemoryAccess);
+                    };
+                    dst.copy_from_slice(src);
+                }
+                Instruction::DataDrop(idx) => {
+                    let Some(data) = self.store.datas.get_mut(*idx as usize) else {
+                        bail!("not found data segment");
+                    };
+                    data.data = vec![];
+                }
+                Instruction::MemoryCopy => {
+                    let len = pop::<i32>(&mut self.stack)? as u32 as usize;
+                    let src = pop::<i32>(&mut self.stack)? as u32 as usize;
+                    let dst = pop::<i32>(&mut self.stack)? as u32 as usize;
+                    let data = &mut self.store.memory.data;
+                    if src + len > data.len() || dst + len > data.len() {
+                        bail!(Trap::OutOfBoundsMemoryAccess);
+                    }
+                    // 領域が重なっていても正しくコピーされる
+                    data.copy_within(src..src + len, dst);
                 }
-                Instruction::I64Add => {
-                    let (Some(rhs), Some(lhs)) = (self.stack.pop(), self.stack.pop()) else {
-                        bail!("not found any value in the stack");
+                Instruction::MemoryFill => {
+                    let len = pop::<i32>(&mut self.stack)? as u32 as usize;
+                    let value = pop::<i32>(&mut self.stack)? as u8;
+                    let dst = pop::<i32>(&mut self.stack)? as u32 as usize;
+                    let Some(dst) = self.store.memory.data.get_mut(dst..dst + len) else {
+                        bail!(Trap::OutOfBoundsMemoryAccess);
                     };
-                    let result = lhs + rhs;
-                    self.stack.push(result);
+                    dst.fill(value);
+                }
+                Instruction::I32Clz => unary_op(&mut self.stack, |value: i32| {
+                    Ok(((value as u32).leading_zeros() as i32).into())
+                })?,
+                Instruction::I32Ctz => unary_op(&mut self.stack, |value: i32| {
+                    Ok(((value as u32).trailing_zeros() as i32).into())
+                })?,
+                Instruction::I32Popcnt => unary_op(&mut self.stack, |value: i32| {
+                    Ok(((value as u32).count_ones() as i32).into())
+                })?,
+                Instruction::I32Add => binary_op(&mut self.stack, |lhs: i32, rhs: i32| {
+                    Ok(lhs.wrapping_add(rhs).into())
+                })?,
+                Instruction::I32Sub => binary_op(&mut self.stack, |lhs: i32, rhs: i32| {
+                    Ok(lhs.wrapping_sub(rhs).into())
+                })?,
+                Instruction::I32Mul => binary_op(&mut self.stack, |lhs: i32, rhs: i32| {
+                    Ok(lhs.wrapping_mul(rhs).into())
+                })?,
+                Instruction::I32DivS => binary_op(&mut self.stack, |lhs: i32, rhs: i32| {
+                    if rhs == 0 {
+                        bail!(Trap::DivideByZero);
+                    }
+                    if lhs == i32::MIN && rhs == -1 {
+                        bail!(Trap::IntegerOverflow);
+                    }
+                    Ok((lhs / rhs).into())
+                })?,
+                Instruction::I32DivU => binary_op(&mut self.stack, |lhs: i32, rhs: i32| {
+                    if rhs == 0 {
+                        bail!(Trap::DivideByZero);
+                    }
+                    Ok(((lhs as u32 / rhs as u32) as i32).into())
+                })?,
+                Instruction::I32RemS => binary_op(&mut self.stack, |lhs: i32, rhs: i32| {
+                    if rhs == 0 {
+                        bail!(Trap::DivideByZero);
+                    }
+                    // i32::MIN % -1 はトラップせず0になる
+                    Ok(lhs.wrapping_rem(rhs).into())
+                })?,
+                Instruction::I32RemU => binary_op(&mut self.stack, |lhs: i32, rhs: i32| {
+                    if rhs == 0 {
+                        bail!(Trap::DivideByZero);
+                    }
+                    Ok(((lhs as u32 % rhs as u32) as i32).into())
+                })?,
+                Instruction::I32And => {
+                    binary_op(&mut self.stack, |lhs: i32, rhs: i32| Ok((lhs & rhs).into()))?
+                }
+                Instruction::I32Or => {
+                    binary_op(&mut self.stack, |lhs: i32, rhs: i32| Ok((lhs | rhs).into()))?
+                }
+                Instruction::I32Xor => {
+                    binary_op(&mut self.stack, |lhs: i32, rhs: i32| Ok((lhs ^ rhs).into()))?
+                }
+                Instruction::I32Shl => binary_op(&mut self.stack, |lhs: i32, rhs: i32| {
+                    Ok((lhs << (rhs & 0x1f)).into())
+                })?,
+                Instruction::I32ShrS => binary_op(&mut self.stack, |lhs: i32, rhs: i32| {
+                    Ok((lhs >> (rhs & 0x1f)).into())
+                })?,
+                Instruction::I32ShrU => binary_op(&mut self.stack, |lhs: i32, rhs: i32| {
+                    Ok((((lhs as u32) >> (rhs & 0x1f)) as i32).into())
+                })?,
+                Instruction::I32Rotl => binary_op(&mut self.stack, |lhs: i32, rhs: i32| {
+                    Ok(((lhs as u32).rotate_left(rhs as u32 & 0x1f) as i32).into())
+                })?,
+                Instruction::I32Rotr => binary_op(&mut self.stack, |lhs: i32, rhs: i32| {
+                    Ok(((lhs as u32).rotate_right(rhs as u32 & 0x1f) as i32).into())
+                })?,
+                Instruction::I64Clz => unary_op(&mut self.stack, |value: i64| {
+                    Ok(((value as u64).leading_zeros() as i64).into())
+                })?,
+                Instruction::I64Ctz => unary_op(&mut self.stack, |value: i64| {
+                    Ok(((value as u64).trailing_zeros() as i64).into())
+                })?,
+                Instruction::I64Popcnt => unary_op(&mut self.stack, |value: i64| {
+                    Ok(((value as u64).count_ones() as i64).into())
+                })?,
+                Instruction::I64Add => binary_op(&mut self.stack, |lhs: i64, rhs: i64| {
+                    Ok(lhs.wrapping_add(rhs).into())
+                })?,
+                Instruction::I64Sub => binary_op(&mut self.stack, |lhs: i64, rhs: i64| {
+                    Ok(lhs.wrapping_sub(rhs).into())
+                })?,
+                Instruction::I64Mul => binary_op(&mut self.stack, |lhs: i64, rhs: i64| {
+                    Ok(lhs.wrapping_mul(rhs).into())
+                })?,
+                Instruction::I64DivS => binary_op(&mut self.stack, |lhs: i64, rhs: i64| {
+                    if rhs == 0 {
+                        bail!(Trap::DivideByZero);
+                    }
+                    if lhs == i64::MIN && rhs == -1 {
+                        bail!(Trap::IntegerOverflow);
+                    }
+                    Ok((lhs / rhs).into())
+                })?,
+                Instruction::I64DivU => binary_op(&mut self.stack, |lhs: i64, rhs: i64| {
+                    if rhs == 0 {
+                        bail!(Trap::DivideByZero);
+                    }
+                    Ok(((lhs as u64 / rhs as u64) as i64).into())
+                })?,
+                Instruction::I64RemS => binary_op(&mut self.stack, |lhs: i64, rhs: i64| {
+                    if rhs == 0 {
+                        bail!(Trap::DivideByZero);
+                    }
+                    // i64::MIN % -1 はトラップせず0になる
+                    Ok(lhs.wrapping_rem(rhs).into())
+                })?,
+                Instruction::I64RemU => binary_op(&mut self.stack, |lhs: i64, rhs: i64| {
+                    if rhs == 0 {
+                        bail!(Trap::DivideByZero);
+                    }
+                    Ok(((lhs as u64 % rhs as u64) as i64).into())
+                })?,
+                Instruction::I64And => {
+                    binary_op(&mut self.stack, |lhs: i64, rhs: i64| Ok((lhs & rhs).into()))?
+                }
+                Instruction::I64Or => {
+                    binary_op(&mut self.stack, |lhs: i64, rhs: i64| Ok((lhs | rhs).into()))?
+                }
+                Instruction::I64Xor => {
+                    binary_op(&mut self.stack, |lhs: i64, rhs: i64| Ok((lhs ^ rhs).into()))?
+                }
+                Instruction::I64Shl => binary_op(&mut self.stack, |lhs: i64, rhs: i64| {
+                    Ok((lhs << (rhs & 0x3f)).into())
+                })?,
+                Instruction::I64ShrS => binary_op(&mut self.stack, |lhs: i64, rhs: i64| {
+                    Ok((lhs >> (rhs & 0x3f)).into())
+                })?,
+                Instruction::I64ShrU => binary_op(&mut self.stack, |lhs: i64, rhs: i64| {
+                    Ok((((lhs as u64) >> (rhs & 0x3f)) as i64).into())
+                })?,
+                Instruction::I64Rotl => binary_op(&mut self.stack, |lhs: i64, rhs: i64| {
+                    Ok(((lhs as u64).rotate_left(rhs as u32 & 0x3f) as i64).into())
+                })?,
+                Instruction::I64Rotr => binary_op(&mut self.stack, |lhs: i64, rhs: i64| {
+                    Ok(((lhs as u64).rotate_right(rhs as u32 & 0x3f) as i64).into())
+                })?,
+                Instruction::F32Abs => {
+                    unary_op(&mut self.stack, |value: f32| Ok(value.abs().into()))?
+                }
+                Instruction::F32Neg => unary_op(&mut self.stack, |value: f32| Ok((-value).into()))?,
+                Instruction::F32Ceil => {
+                    unary_op(&mut self.stack, |value: f32| Ok(value.ceil().into()))?
+                }
+                Instruction::F32Floor => {
+                    unary_op(&mut self.stack, |value: f32| Ok(value.floor().into()))?
+                }
+                Instruction::F32Trunc => {
+                    unary_op(&mut self.stack, |value: f32| Ok(value.trunc().into()))?
+                }
+                // nearestは偶数丸め(roundは0から離れる方向に丸めるので使えない)
+                Instruction::F32Nearest => unary_op(&mut self.stack, |value: f32| {
+                    Ok(value.round_ties_even().into())
+                })?,
+                Instruction::F32Sqrt => {
+                    unary_op(&mut self.stack, |value: f32| Ok(value.sqrt().into()))?
+                }
+                Instruction::F32Add => {
+                    binary_op(&mut self.stack, |lhs: f32, rhs: f32| Ok((lhs + rhs).into()))?
+                }
+                Instruction::F32Sub => {
+                    binary_op(&mut self.stack, |lhs: f32, rhs: f32| Ok((lhs - rhs).into()))?
+                }
+                Instruction::F32Mul => {
+                    binary_op(&mut self.stack, |lhs: f32, rhs: f32| Ok((lhs * rhs).into()))?
+                }
+                // 浮動小数点数の0除算はトラップせずinfかNaNになる
+                Instruction::F32Div => {
+                    binary_op(&mut self.stack, |lhs: f32, rhs: f32| Ok((lhs / rhs).into()))?
+                }
+                Instruction::F64Abs => {
+                    unary_op(&mut self.stack, |value: f64| Ok(value.abs().into()))?
+                }
+                Instruction::F64Neg => unary_op(&mut self.stack, |value: f64| Ok((-value).into()))?,
+                Instruction::F64Ceil => {
+                    unary_op(&mut self.stack, |value: f64| Ok(value.ceil().into()))?
+                }
+                Instruction::F64Floor => {
+                    unary_op(&mut self.stack, |value: f64| Ok(value.floor().into()))?
+                }
+                Instruction::F64Trunc => {
+                    unary_op(&mut self.stack, |value: f64| Ok(value.trunc().into()))?
+                }
+                // nearestは偶数丸め(roundは0から離れる方向に丸めるので使えない)
+                Instruction::F64Nearest => unary_op(&mut self.stack, |value: f64| {
+                    Ok(value.round_ties_even().into())
+                })?,
+                Instruction::F64Sqrt => {
+                    unary_op(&mut self.stack, |value: f64| Ok(value.sqrt().into()))?
+                }
+                Instruction::F64Add => {
+                    binary_op(&mut self.stack, |lhs: f64, rhs: f64| Ok((lhs + rhs).into()))?
+                }
+                Instruction::F64Sub => {
+                    binary_op(&mut self.stack, |lhs: f64, rhs: f64| Ok((lhs - rhs).into()))?
+                }
+                Instruction::F64Mul => {
+                    binary_op(&mut self.stack, |lhs: f64, rhs: f64| Ok((lhs * rhs).into()))?
+                }
+                Instruction::F64Div => {
+                    binary_op(&mut self.stack, |lhs: f64, rhs: f64| Ok((lhs / rhs).into()))?
                 }
             }
         }
         Ok(())
     }
 
+    // 現在のフレームのdepth番目のラベルに分岐する
+    fn branch(&mut self, depth: u32) -> Result<()> {
+        let Some(frame) = self.call_stack.last_mut() else {
+            bail!("not found frame");
+        };
+        let depth = depth as usize;
+
+        // 一番外側のラベルは関数本体なので、フレームから抜ける
+        if depth == frame.labels.len() {
+            let Some(frame) = self.call_stack.pop() else {
+                bail!("not found frame");
+            };
+            let Frame { sp, arity, .. } = frame;
+            return stack_unwind(&mut self.stack, sp, arity);
+        }
+
+        let Some(idx) = frame.labels.len().checked_sub(depth + 1) else {
+            bail!("not found label");
+        };
+        let Label {
+            kind,
+            pc,
+            sp,
+            arity,
+        } = frame.labels[idx].clone();
+        stack_unwind(&mut self.stack, sp, arity)?;
+
+        match kind {
+            // loopは先頭に戻るので、ラベルは残しておく
+            LabelKind::Loop => frame.labels.truncate(idx + 1),
+            // block, ifはendの次に進むので、ラベルもpopする
+            LabelKind::Block | LabelKind::If => frame.labels.truncate(idx),
+        }
+        frame.pc = pc as isize;
+        Ok(())
+    }
+
+    // エクスポートの名前と種類(順不同)
+    pub fn exports(&self) -> impl Iterator<Item = (&str, &ExportDesc)> {
+        self.store
+            .module
+            .exports
+            .values()
+            .map(|export| (export.name.as_str(), &export.desc))
+    }
+
+    // エクスポートされている関数の名前(名前順)
+    pub fn exported_functions(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .exports()
+            .filter(|(_, desc)| matches!(desc, ExportDesc::Func(_)))
+            .map(|(name, _)| name.to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    // トラップするまでにストアへ加えた変更はデフォルトでは残る
+    // rollback_on_trapを有効にしたときだけ呼び出し前の状態に戻す
     pub fn call(&mut self, name: impl Into<String>, args: Vec<Value>) -> Result<Option<Value>> {
-        let idx = match self
+        // 戻り値が複数ある場合はスタックの一番上の値を返す
+        Ok(self.call_multi(name, args)?.pop())
+    }
+
+    // 戻り値をすべて返す(multi-value)
+    pub fn call_multi(&mut self, name: impl Into<String>, args: Vec<Value>) -> Result<Vec<Value>> {
+        let idx = self.export_func_index(&name.into())?;
+        self.call_by_func_index(idx, args)
+    }
+
+    // エクスポートされていない関数もインデックスを指定して呼び出す
+    pub fn call_by_index(&mut self, idx: usize, args: Vec<Value>) -> Result<Option<Value>> {
+        if idx >= self.store.funcs.len() {
+            bail!("function index {} out of range", idx)
+        }
+        Ok(self.call_by_func_index(idx, args)?.pop())
+    }
+
+    // エクスポートされた関数のシグネチャ
+    pub fn func_type(&self, name: &str) -> Result<&FuncType> {
+        let idx = self.export_func_index(name)?;
+        let Some(func_inst) = self.store.funcs.get(idx) else {
+            bail!("not found func")
+        };
+        Ok(func_inst.func_type())
+    }
+
+    fn export_func_index(&self, name: &str) -> Result<usize> {
+        let export = self
             .store
             .module
             .exports
-            .get(&name.into())
-            .ok_or(anyhow!("not found export function"))?
-            .desc
-        {
-            ExportDesc::Func(idx) => idx as usize,
-        };
+            .get(name)
+            .ok_or(anyhow!("not found export function"))?;
+        match export.desc {
+            ExportDesc::Func(idx) => Ok(idx as usize),
+            _ => bail!("export {} is not a function", name),
+        }
+    }
+
+    fn call_by_func_index(&mut self, idx: usize, args: Vec<Value>) -> Result<Vec<Value>> {
         let Some(func_inst) = self.store.funcs.get(idx) else {
             bail!("not found func")
         };
+        check_args(func_inst.func_type(), &args)?;
+        let func_inst = func_inst.clone();
+
+        // エラー時は呼び出し前の高さまで戻す(ホスト関数から呼ばれた場合も外側のフレームは残す)
+        let height = self.stack.len();
+        let depth = self.call_stack.len();
+        // 線形メモリの複製は重いので、有効なときに最も外側の呼び出しでだけ退避する
+        let snapshot = (self.rollback_on_trap && depth == 0).then(|| self.snapshot());
+
         for arg in args {
             self.stack.push(arg);
         }
-        match func_inst {
-            FuncInst::Internal(func) => self.invoke_internal(func.clone()),
+        let result = match func_inst {
+            FuncInst::Internal(func) => self.invoke_internal(idx as u32, func),
+            FuncInst::External(func) => {
+                let arity = func.func_type.results.len();
+                self.invoke_external(func).and_then(|()| {
+                    match self.stack.len().checked_sub(arity) {
+                        Some(bottom) => Ok(self.stack.split_off(bottom)),
+                        None => Err(Trap::StackUnderflow.into()),
+                    }
+                })
+            }
+        };
+        if result.is_err() {
+            self.stack.truncate(height);
+            self.call_stack.truncate(depth);
+            if let Some(snapshot) = snapshot {
+                self.restore(snapshot);
+            }
         }
+        result
+    }
+
+    // ホスト側から線形メモリを読み取る
+    pub fn read_memory(&self, offset: usize, len: usize) -> Result<&[u8]> {
+        let Some(end) = offset.checked_add(len) else {
+            bail!(Trap::OutOfBoundsMemoryAccess);
+        };
+        let Some(bytes) = self.store.memory.data.get(offset..end) else {
+            bail!(Trap::OutOfBoundsMemoryAccess);
+        };
+        Ok(bytes)
+    }
+
+    // ホスト側から線形メモリに書き込む
+    pub fn write_memory(&mut self, offset: usize, bytes: &[u8]) -> Result<()> {
+        let Some(end) = offset.checked_add(bytes.len()) else {
+            bail!(Trap::OutOfBoundsMemoryAccess);
+        };
+        let Some(dst) = self.store.memory.data.get_mut(offset..end) else {
+            bail!(Trap::OutOfBoundsMemoryAccess);
+        };
+        dst.copy_from_slice(bytes);
+        Ok(())
     }
 
-    fn invoke_internal(&mut self, func: InternalFuncInst) -> Result<Option<Value>> {
+    // 引数をスタックからpopしてフレームを作成し、コールスタックにpushする
+    fn push_frame(&mut self, func_idx: u32, func: &InternalFuncInst) -> Result<()> {
+        if self.call_stack.len() >= self.max_call_depth {
+            bail!(Trap::CallStackExhausted);
+        }
+
         // 関数の引数の個数
-        let bottom = self.stack.len() - func.func_type.params.len();
+        let Some(bottom) = self.stack.len().checked_sub(func.func_type.params.len()) else {
+            bail!(Trap::StackUnderflow);
+        };
 
         // 引数の数、スタックから値をpop
         let mut locals = self.stack.split_off(bottom);
@@ -116,6 +1206,9 @@ impl Runtime {
             match local {
                 ValueType::I32 => locals.push(Value::I32(0)),
                 ValueType::I64 => locals.push(Value::I64(0)),
+                ValueType::F32 => locals.push(Value::F32(0.0)),
+                ValueType::F64 => locals.push(Value::F64(0.0)),
+                ValueType::FuncRef => locals.push(Value::FuncRef(None)),
             }
         }
 
@@ -123,66 +1216,2205 @@ impl Runtime {
         let arity = func.func_type.results.len();
 
         let frame = Frame {
+            func_idx,
             pc: -1,
             sp: self.stack.len(),
-            insts: func.code.body.clone(),
+            insts: Rc::clone(&func.code.body),
+            block_addrs: Rc::clone(&func.code.block_addrs),
             arity,
             locals,
+            labels: vec![],
         };
 
         // コールスタックにフレームをpush
         self.call_stack.push(frame);
+        Ok(())
+    }
+
+    fn invoke_internal(&mut self, func_idx: u32, func: InternalFuncInst) -> Result<Vec<Value>> {
+        let arity = func.func_type.results.len();
+        self.push_frame(func_idx, &func)?;
 
         // 実行
         if let Err(e) = self.execute() {
-            self.cleanup();
-            bail!("failed to execute instructions: {}", e)
+            // ホスト関数を挟んで呼ばれた場合は、最も内側で取得したものを使う
+            let e = if e.downcast_ref::<Backtrace>().is_some() {
+                e
+            } else {
+                match e.downcast::<Trap>() {
+                    // エラーメッセージはトラップのままにして、原因としてコールスタックをつなげる
+                    Ok(trap) => {
+                        let frames = self.call_stack.iter().rev().map(|f| f.func_idx).collect();
+                        anyhow::Error::new(Backtrace { frames }).context(trap)
+                    }
+                    Err(e) => e,
+                }
+            };
+            return Err(e);
         }
 
-        if arity > 0 {
-            let Some(value) = self.stack.pop() else {
-                bail!("not found return value")
-            };
-            return Ok(Some(value));
+        // 戻り値の個数分、スタックから値をpop
+        let Some(bottom) = self.stack.len().checked_sub(arity) else {
+            bail!(Trap::StackUnderflow)
+        };
+        Ok(self.stack.split_off(bottom))
+    }
+
+    // 引数をスタックからpopしてホスト関数を呼び出し、戻り値をスタックにpushする
+    fn invoke_external(&mut self, func: ExternalFuncInst) -> Result<()> {
+        let Some(host_func) = self.imports.get_func(&func.module, &func.field) else {
+            bail!("not found host function: {}.{}", func.module, func.field)
+        };
+        let Some(bottom) = self.stack.len().checked_sub(func.func_type.params.len()) else {
+            bail!(Trap::StackUnderflow);
+        };
+        let args = self.stack.split_off(bottom);
+        let result = host_func(self, args)?;
+        // 戻り値の個数と型がインポートのシグネチャと一致するか確認する
+        match (result, &func.func_type.results[..]) {
+            (None, []) => {}
+            (Some(value), [value_type]) if value.ty() == *value_type => self.stack.push(value),
+            (result, results) => bail!(
+                "host function {}.{} returned {:?}, expected {:?}",
+                func.module,
+                func.field,
+                result,
+                results
+            ),
+        }
+        Ok(())
+    }
+
+    // スタック、コールスタック、グローバル変数、テーブル、線形メモリを複製して保存する
+    pub fn snapshot(&self) -> RuntimeSnapshot {
+        RuntimeSnapshot {
+            stack: self.stack.clone(),
+            call_stack: self.call_stack.clone(),
+            globals: self.store.globals.clone(),
+            table: self.store.table.clone(),
+            memory: self.store.memory.clone(),
+            datas: self.store.datas.clone(),
         }
-        Ok(None)
     }
 
-    fn cleanup(&mut self) {
+    // snapshotで保存した状態に戻す
+    pub fn restore(&mut self, snapshot: RuntimeSnapshot) {
+        self.stack = snapshot.stack;
+        self.call_stack = snapshot.call_stack;
+        self.store.globals = snapshot.globals;
+        self.store.table = snapshot.table;
+        self.store.memory = snapshot.memory;
+        self.store.datas = snapshot.datas;
+    }
+
+    // トラップ後などに、ストアを残したままスタックとコールスタックを空にする
+    pub fn reset(&mut self) {
         self.stack = vec![];
         self.call_stack = vec![];
     }
 }
 
-pub fn stack_unwind(stack: &mut Vec<Value>, sp: usize, arity: usize) -> Result<()> {
-    if arity > 0 {
-        let Some(value) = stack.pop() else {
-            bail!("not found return value");
-        };
-        stack.drain(sp..);
-        stack.push(value);
-    } else {
-        stack.drain(sp..);
+// 関数とグローバル変数のインポートがすべて登録済みで、関数のシグネチャが一致するか確認する
+fn resolve_imports(module: &Module, imports: &Imports) -> Result<()> {
+    let types = module.type_section.as_deref().unwrap_or_default();
+    for import in module.import_section.iter().flatten() {
+        let (module, field) = (&import.module, &import.field);
+        match import.desc {
+            ImportDesc::Func(type_idx) => {
+                let Some(func_type) = imports.get_func_type(module, field) else {
+                    bail!("unsatisfied import: {}.{}", module, field);
+                };
+                // ホスト関数のシグネチャがインポートの型と一致するか確認する
+                if !types
+                    .get(type_idx as usize)
+                    .is_some_and(|expect| func_type.matches(expect))
+                {
+                    bail!("incompatible import type: {}.{}", module, field);
+                }
+            }
+            ImportDesc::Global(_) if imports.get_global(module, field).is_none() => {
+                bail!("unsatisfied import: {}.{}", module, field);
+            }
+            _ => {}
+        }
     }
     Ok(())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::Runtime;
-    use crate::execution::value::Value;
-    use anyhow::Result;
+// 引数の個数と型が関数のシグネチャと一致するか確認する
+fn check_args(func_type: &FuncType, args: &[Value]) -> Result<()> {
+    if args.len() != func_type.params.len() {
+        bail!(
+            "expected {} arguments, found {}",
+            func_type.params.len(),
+            args.len()
+        )
+    }
+    for (i, (arg, param)) in args.iter().zip(&func_type.params).enumerate() {
+        let arg_type = arg.ty();
+        if arg_type != *param {
+            bail!(
+                "type mismatch in argument {}: expected {:?}, found {:?}",
+                i,
+                param,
+                arg_type
+            )
+        }
+    }
+    Ok(())
+}
 
-    #[test]
-    fn execute_export_start_i64add() -> Result<()> {
-        let wasm = wat::parse_file("src/fixtures/func_export_start_i64add.wat")?;
-        let mut runtime = Runtime::instantiate(wasm)?;
-        let tests = vec![(2, 3, 5)];
+// スタックから値をpopし、指定した型に変換する
+fn pop<T>(stack: &mut Vec<Value>) -> Result<T>
+where
+    T: TryFrom<Value, Error = anyhow::Error>,
+{
+    let Some(value) = stack.pop() else {
+        bail!(Trap::StackUnderflow);
+    };
+    value.try_into()
+}
 
-        for (lhs, rhs, want) in tests {
-            let args = vec![Value::I64(lhs), Value::I64(rhs)];
-            let result = runtime.call("_start", args)?;
-            assert_eq!(result, Some(Value::I64(want)))
+// 整数に変換できる範囲(minは含み、maxは含まない)
+const I32_MIN: f64 = -2147483648.0; // -2^31
+const I32_MAX: f64 = 2147483648.0; // 2^31
+const U32_MAX: f64 = 4294967296.0; // 2^32
+const I64_MIN: f64 = -9223372036854775808.0; // -2^63
+const I64_MAX: f64 = 9223372036854775808.0; // 2^63
+const U64_MAX: f64 = 18446744073709551616.0; // 2^64
+
+// 浮動小数点数を0方向に丸め、変換先の整数の範囲に収まらなければトラップする
+// f32はf64に正確に変換できるので、範囲チェックはf64で行う
+fn trunc_float(value: f64, min: f64, max: f64) -> Result<f64> {
+    if value.is_nan() {
+        bail!(Trap::InvalidConversionToInteger);
+    }
+    let value = value.trunc();
+    // 無限大もここで弾かれる
+    if !(min..max).contains(&value) {
+        bail!(Trap::IntegerOverflow);
+    }
+    Ok(value)
+}
+
+// 線形メモリのaddr+offsetの位置からNバイト読み取る
+fn read_memory<const N: usize>(memory: &MemoryInst, addr: i32, offset: u32) -> Result<[u8; N]> {
+    // アドレスはu32として解釈する
+    let start = addr as u32 as usize + offset as usize;
+    let Some(bytes) = memory.data.get(start..start + N) else {
+        bail!(Trap::OutOfBoundsMemoryAccess);
+    };
+    Ok(bytes.try_into()?)
+}
+
+// 線形メモリのaddr+offsetの位置にバイト列を書き込む
+fn write_memory(memory: &mut MemoryInst, addr: i32, offset: u32, bytes: &[u8]) -> Result<()> {
+    let start = addr as u32 as usize + offset as usize;
+    let Some(dst) = memory.data.get_mut(start..start + bytes.len()) else {
+        bail!(Trap::OutOfBoundsMemoryAccess);
+    };
+    dst.copy_from_slice(bytes);
+    Ok(())
+}
+
+// スタックから1つの値をpopし、演算結果をスタックにpushする
+fn unary_op<T>(stack: &mut Vec<Value>, op: impl FnOnce(T) -> Result<Value>) -> Result<()>
+where
+    T: TryFrom<Value, Error = anyhow::Error>,
+{
+    let Some(value) = stack.pop() else {
+        bail!(Trap::StackUnderflow);
+    };
+    let result = op(value.try_into()?)?;
+    stack.push(result);
+    Ok(())
+}
+
+// スタックから2つの値をpopし、演算結果をスタックにpushする
+// オペランドの型が一致しない場合はトラップする
+fn binary_op<T>(stack: &mut Vec<Value>, op: impl FnOnce(T, T) -> Result<Value>) -> Result<()>
+where
+    T: TryFrom<Value, Error = anyhow::Error>,
+{
+    let (Some(rhs), Some(lhs)) = (stack.pop(), stack.pop()) else {
+        bail!(Trap::StackUnderflow);
+    };
+    let result = op(lhs.try_into()?, rhs.try_into()?)?;
+    stack.push(result);
+    Ok(())
+}
+
+// 現在のpcの位置から始まるブロックに対応するelseとendの位置
+fn block_address(frame: &Frame) -> Result<BlockAddress> {
+    let Some(Some(addr)) = frame.block_addrs.get(frame.pc as usize) else {
+        bail!("not found end of block")
+    };
+    Ok(*addr)
+}
+
+// ブロックの引数と戻り値の個数
+fn block_arity(block_type: &BlockType, func_types: &[FuncType]) -> Result<(usize, usize)> {
+    match block_type {
+        BlockType::Void => Ok((0, 0)),
+        BlockType::Value(_) => Ok((0, 1)),
+        BlockType::TypeIndex(idx) => {
+            let Some(func_type) = func_types.get(*idx as usize) else {
+                bail!("not found block type");
+            };
+            Ok((func_type.params.len(), func_type.results.len()))
+        }
+    }
+}
+
+// スタックの上からarity個の値を残して、spの位置までスタックを戻す
+pub fn stack_unwind(stack: &mut Vec<Value>, sp: usize, arity: usize) -> Result<()> {
+    let Some(bottom) = stack.len().checked_sub(arity) else {
+        bail!(Trap::StackUnderflow);
+    };
+    let values = stack.split_off(bottom);
+    stack.truncate(sp);
+    stack.extend(values);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Runtime, RuntimeBuilder};
+    use crate::{
+        binary::{
+            instruction::Instruction,
+            types::{ExportDesc, FuncType, ValueType},
+        },
+        execution::{
+            import::Imports,
+            store::{ExportInst, Func, FuncInst, GlobalInst, InternalFuncInst, ModuleInst, Store},
+            trap::{Backtrace, Trap},
+            value::Value,
+        },
+    };
+    use anyhow::{bail, Result};
+    use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+    #[test]
+    fn execute_export_start_i64add() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_export_start_i64add.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let tests = vec![(2, 3, 5)];
+
+        for (lhs, rhs, want) in tests {
+            let args = vec![Value::I64(lhs), Value::I64(rhs)];
+            let result = runtime.call("_start", args)?;
+            assert_eq!(result, Some(Value::I64(want)))
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn execute_i32sub() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_export_i32sub.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let tests = vec![(5, 8, -3), (i32::MIN, 1, i32::MAX)];
+
+        for (lhs, rhs, want) in tests {
+            let args = vec![Value::I32(lhs), Value::I32(rhs)];
+            let result = runtime.call("sub", args)?;
+            assert_eq!(result, Some(Value::I32(want)))
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn execute_mul() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_export_mul.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+
+        let tests = vec![(6, 7, 42), (i32::MAX, 2, -2)];
+        for (lhs, rhs, want) in tests {
+            let args = vec![Value::I32(lhs), Value::I32(rhs)];
+            let result = runtime.call("i32_mul", args)?;
+            assert_eq!(result, Some(Value::I32(want)))
+        }
+
+        let tests = vec![(6, 7, 42), (i64::MAX, 2, -2)];
+        for (lhs, rhs, want) in tests {
+            let args = vec![Value::I64(lhs), Value::I64(rhs)];
+            let result = runtime.call("i64_mul", args)?;
+            assert_eq!(result, Some(Value::I64(want)))
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn execute_i32div() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_export_i32div.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let tests = vec![
+            ("div_s", 7, 2, 3),
+            ("div_s", -7, 2, -3),
+            ("div_u", -1, 2, i32::MAX),
+            ("div_u", 7, 2, 3),
+        ];
+
+        for (name, lhs, rhs, want) in tests {
+            let args = vec![Value::I32(lhs), Value::I32(rhs)];
+            let result = runtime.call(name, args)?;
+            assert_eq!(result, Some(Value::I32(want)))
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn execute_i32div_trap() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_export_i32div.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let tests = vec![("div_s", 1, 0), ("div_u", 1, 0), ("div_s", i32::MIN, -1)];
+
+        for (name, lhs, rhs) in tests {
+            let args = vec![Value::I32(lhs), Value::I32(rhs)];
+            let result = runtime.call(name, args);
+            assert!(result.is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn execute_i32rem() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_export_i32rem.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let tests = vec![
+            ("rem_s", 7, 3, 1),
+            ("rem_s", -7, 3, -1),
+            ("rem_s", i32::MIN, -1, 0),
+            ("rem_u", -7, 3, 0),
+            ("rem_u", 7, 3, 1),
+        ];
+
+        for (name, lhs, rhs, want) in tests {
+            let args = vec![Value::I32(lhs), Value::I32(rhs)];
+            let result = runtime.call(name, args)?;
+            assert_eq!(result, Some(Value::I32(want)))
+        }
+
+        for name in ["rem_s", "rem_u"] {
+            let args = vec![Value::I32(1), Value::I32(0)];
+            assert!(runtime.call(name, args).is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn execute_i64arith() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_export_i64arith.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let tests = vec![
+            ("sub", 5, 8, -3),
+            ("sub", i64::MIN, 1, i64::MAX),
+            // i32に切り詰めると 0 / 2 = 0 になってしまうケース
+            ("div_s", 0x1_0000_0000, 2, 0x8000_0000),
+            ("div_s", -7, 2, -3),
+            ("div_u", -1, 2, i64::MAX),
+            ("rem_s", -7, 3, -1),
+            ("rem_s", i64::MIN, -1, 0),
+            ("rem_u", 0x1_0000_0005, 0x1_0000_0000, 5),
+        ];
+
+        for (name, lhs, rhs, want) in tests {
+            let args = vec![Value::I64(lhs), Value::I64(rhs)];
+            let result = runtime.call(name, args)?;
+            assert_eq!(result, Some(Value::I64(want)))
+        }
+
+        let tests = vec![
+            ("div_s", 1, 0),
+            ("div_u", 1, 0),
+            ("rem_s", 1, 0),
+            ("rem_u", 1, 0),
+            ("div_s", i64::MIN, -1),
+        ];
+        for (name, lhs, rhs) in tests {
+            let args = vec![Value::I64(lhs), Value::I64(rhs)];
+            assert!(runtime.call(name, args).is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn execute_i64arith_type_mismatch() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_export_i64arith.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let args = vec![Value::I32(1), Value::I32(2)];
+        assert!(runtime.call("sub", args).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn execute_i32bitwise() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_export_i32bitwise.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let tests = vec![
+            ("and", 0xFF0F, 0x0FF0, 0x0F00),
+            ("or", 0xFF00, 0x00F0, 0xFFF0),
+            ("xor", 0xFF0F, 0x0FF0, 0xF0FF),
+        ];
+
+        for (name, lhs, rhs, want) in tests {
+            let args = vec![Value::I32(lhs), Value::I32(rhs)];
+            let result = runtime.call(name, args)?;
+            assert_eq!(result, Some(Value::I32(want)))
+        }
+
+        let args = vec![Value::I64(1), Value::I64(2)];
+        assert!(runtime.call("and", args).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn execute_i32shift() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_export_i32shift.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let tests = vec![
+            ("shl", 1, 4, 16),
+            ("shl", 1, 31, i32::MIN),
+            ("shr_s", -16, 2, -4),
+            ("shr_u", -16, 2, 0x3FFF_FFFC),
+            // シフト量は31でマスクされるので33は1として扱われる
+            ("shl", 1, 33, 2),
+            ("shr_s", -16, 33, -8),
+            ("shr_u", -16, 33, 0x7FFF_FFF8),
+        ];
+
+        for (name, lhs, rhs, want) in tests {
+            let args = vec![Value::I32(lhs), Value::I32(rhs)];
+            let result = runtime.call(name, args)?;
+            assert_eq!(result, Some(Value::I32(want)))
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn execute_i32rotate() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_export_i32rotate.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let tests = vec![
+            ("rotl", 0x8000_0000_u32 as i32, 1, 0x1),
+            ("rotl", 0x1234_5678, 36, 0x2345_6781),
+            ("rotr", 0x1, 1, 0x8000_0000_u32 as i32),
+            ("rotr", 0x1234_5678, 4, 0x8123_4567_u32 as i32),
+        ];
+
+        for (name, lhs, rhs, want) in tests {
+            let args = vec![Value::I32(lhs), Value::I32(rhs)];
+            let result = runtime.call(name, args)?;
+            assert_eq!(result, Some(Value::I32(want)))
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn execute_i32bitcount() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_export_i32bitcount.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let tests = vec![
+            ("clz", 0, 32),
+            ("clz", 1, 31),
+            ("clz", -1, 0),
+            ("ctz", 0, 32),
+            ("ctz", 8, 3),
+            ("popcnt", 0xFFFF, 16),
+            ("popcnt", -1, 32),
+        ];
+
+        for (name, arg, want) in tests {
+            let result = runtime.call(name, vec![Value::I32(arg)])?;
+            assert_eq!(result, Some(Value::I32(want)))
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn execute_i64bitcount() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_export_i64bitcount.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let tests = vec![
+            ("clz", 0, 64),
+            ("clz", 1, 63),
+            ("ctz", 0, 64),
+            ("ctz", 0x1_0000_0000, 32),
+            ("popcnt", i64::MAX, 63),
+            ("popcnt", -1, 64),
+        ];
+
+        for (name, arg, want) in tests {
+            let result = runtime.call(name, vec![Value::I64(arg)])?;
+            assert_eq!(result, Some(Value::I64(want)))
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn execute_i64bitwise() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_export_i64bitwise.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let tests = vec![
+            ("and", 0xFF0F_0000_0000, 0x0FF0_0000_0000, 0x0F00_0000_0000),
+            ("or", 0xFF00_0000_0000, 0x00F0, 0xFF00_0000_00F0),
+            ("xor", 0xFF0F, 0x0FF0, 0xF0FF),
+            ("shl", 1, 32, 0x1_0000_0000),
+            // シフト量は63でマスクされるので65は1として扱われる
+            ("shl", 1, 65, 2),
+            ("shr_s", -16, 2, -4),
+            ("shr_u", -16, 60, 0xF),
+            ("rotl", i64::MIN, 1, 1),
+            ("rotr", 1, 1, i64::MIN),
+        ];
+
+        for (name, lhs, rhs, want) in tests {
+            let args = vec![Value::I64(lhs), Value::I64(rhs)];
+            let result = runtime.call(name, args)?;
+            assert_eq!(result, Some(Value::I64(want)))
+        }
+
+        let args = vec![Value::I32(1), Value::I32(2)];
+        assert!(runtime.call("and", args).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn execute_i32eq() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_export_i32eq.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let tests = vec![
+            ("eq", 3, 3, 1),
+            ("eq", 3, 4, 0),
+            ("ne", 3, 3, 0),
+            ("ne", 3, 4, 1),
+        ];
+
+        for (name, lhs, rhs, want) in tests {
+            let args = vec![Value::I32(lhs), Value::I32(rhs)];
+            let result = runtime.call(name, args)?;
+            assert_eq!(result, Some(Value::I32(want)))
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn execute_i32cmp() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_export_i32cmp.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let tests = vec![
+            // 符号なしで比較すると-1は0xFFFFFFFFとして扱われる
+            ("lt_s", -1, 0, 1),
+            ("lt_u", -1, 0, 0),
+            ("gt_s", -1, 0, 0),
+            ("gt_u", -1, 0, 1),
+            ("le_s", 2, 2, 1),
+            ("le_u", 3, 2, 0),
+            ("ge_s", 2, 3, 0),
+            ("ge_u", -1, -1, 1),
+        ];
+
+        for (name, lhs, rhs, want) in tests {
+            let args = vec![Value::I32(lhs), Value::I32(rhs)];
+            let result = runtime.call(name, args)?;
+            assert_eq!(result, Some(Value::I32(want)), "{name}({lhs}, {rhs})")
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn execute_eqz() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_export_eqz.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let tests = vec![
+            ("i32_eqz", Value::I32(0), 1),
+            ("i32_eqz", Value::I32(5), 0),
+            ("i64_eqz", Value::I64(0), 1),
+            ("i64_eqz", Value::I64(5), 0),
+        ];
+
+        for (name, arg, want) in tests {
+            let result = runtime.call(name, vec![arg])?;
+            assert_eq!(result, Some(Value::I32(want)))
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn execute_i64cmp() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_export_i64cmp.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let tests = vec![
+            ("eq", 0x1_0000_0000, 0x1_0000_0000, 1),
+            // 下位32bitが同じでも等しくない
+            ("eq", 0x1_0000_0000, 0, 0),
+            ("ne", 0x1_0000_0000, 0, 1),
+            ("lt_s", -1, 0, 1),
+            ("lt_u", -1, 0, 0),
+            ("gt_s", -1, 0, 0),
+            ("gt_u", -1, 0, 1),
+            ("le_s", 2, 2, 1),
+            ("le_u", 3, 2, 0),
+            ("ge_s", 2, 3, 0),
+            ("ge_u", -1, -1, 1),
+        ];
+
+        for (name, lhs, rhs, want) in tests {
+            let args = vec![Value::I64(lhs), Value::I64(rhs)];
+            let result = runtime.call(name, args)?;
+            // 比較結果はオペランドが64bitでもi32になる
+            assert_eq!(result, Some(Value::I32(want)), "{name}({lhs}, {rhs})")
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn execute_call() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_call.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let tests = vec![(1, 3), (7, 21), (-2, -6)];
+
+        for (arg, want) in tests {
+            let result = runtime.call("triple", vec![Value::I64(arg)])?;
+            assert_eq!(result, Some(Value::I64(want)))
+        }
+        // 呼び出し後はスタックが元に戻っている
+        assert!(runtime.stack.is_empty());
+        assert!(runtime.call_stack.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn execute_local_set() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_local_set.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let result = runtime.call("set", vec![Value::I32(42)])?;
+        assert_eq!(result, Some(Value::I32(42)));
+        Ok(())
+    }
+
+    #[test]
+    fn execute_local_tee() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_local_tee.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let result = runtime.call("tee", vec![Value::I32(21)])?;
+        assert_eq!(result, Some(Value::I32(42)));
+        Ok(())
+    }
+
+    fn global_counter_runtime(mutable: bool) -> Runtime {
+        // モジュールを介さずにStoreを直接組み立てる
+        let func = InternalFuncInst {
+            func_type: FuncType {
+                params: vec![ValueType::I32],
+                results: vec![ValueType::I32],
+            },
+            code: Func::new(
+                vec![],
+                vec![
+                    Instruction::GlobalGet(0),
+                    Instruction::LocalGet(0),
+                    Instruction::I32Add,
+                    Instruction::GlobalSet(0),
+                    Instruction::GlobalGet(0),
+                    Instruction::End,
+                ],
+            )
+            .expect("valid function body"),
+        };
+        let export = ExportInst {
+            name: "inc".to_string(),
+            desc: ExportDesc::Func(0),
+        };
+        Runtime {
+            store: Store {
+                funcs: vec![FuncInst::Internal(func)],
+                globals: vec![GlobalInst {
+                    value: Value::I32(0),
+                    mutable,
+                }],
+                module: ModuleInst {
+                    exports: HashMap::from([("inc".to_string(), export)]),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn execute_global_get_set() -> Result<()> {
+        let mut runtime = global_counter_runtime(true);
+        assert_eq!(
+            runtime.call("inc", vec![Value::I32(1)])?,
+            Some(Value::I32(1))
+        );
+        assert_eq!(
+            runtime.call("inc", vec![Value::I32(1)])?,
+            Some(Value::I32(2))
+        );
+        assert_eq!(runtime.store.globals[0].value, Value::I32(2));
+        Ok(())
+    }
+
+    #[test]
+    fn execute_global_set_immutable() {
+        let mut runtime = global_counter_runtime(false);
+        assert!(runtime.call("inc", vec![Value::I32(1)]).is_err());
+        assert_eq!(runtime.store.globals[0].value, Value::I32(0));
+    }
+
+    #[test]
+    fn execute_global_from_section() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/global_counter.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        assert_eq!(
+            runtime.call("inc", vec![Value::I32(1)])?,
+            Some(Value::I32(8))
+        );
+        assert_eq!(
+            runtime.call("inc", vec![Value::I32(1)])?,
+            Some(Value::I32(9))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn execute_i32_load_store() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/memory_i32.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let tests = vec![(0, 42), (4, -1), (65532, i32::MAX)];
+
+        for (addr, value) in tests {
+            let args = vec![Value::I32(addr), Value::I32(value)];
+            let result = runtime.call("store_load", args)?;
+            assert_eq!(result, Some(Value::I32(value)))
+        }
+        assert_eq!(runtime.store.memory.data[0..4], 42_i32.to_le_bytes());
+        Ok(())
+    }
+
+    #[test]
+    fn execute_i32_load_store_out_of_bounds() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/memory_i32.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        for addr in [65533, -1] {
+            let args = vec![Value::I32(addr), Value::I32(1)];
+            assert!(runtime.call("store_load", args).is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn execute_i64_load_store() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/memory_i64.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let tests = vec![(0, i64::MAX), (8, -1), (65528, i64::MIN)];
+
+        for (addr, value) in tests {
+            let args = vec![Value::I32(addr), Value::I64(value)];
+            let result = runtime.call("store_load", args)?;
+            assert_eq!(result, Some(Value::I64(value)))
+        }
+
+        // addr + offset + 8 がメモリサイズを超えるとトラップする
+        let args = vec![Value::I32(65529), Value::I64(1)];
+        assert!(runtime.call("store_load", args).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn execute_i32_narrow_load() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/memory_i32_narrow_load.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        runtime.store.memory.data[0..4].copy_from_slice(&[0x80, 0xFF, 0x34, 0x12]);
+        let tests = vec![
+            // 符号付きは符号拡張、符号なしはゼロ拡張される
+            ("load8_s", 0, -128),
+            ("load8_u", 0, 128),
+            ("load8_s", 1, -1),
+            ("load8_u", 1, 255),
+            ("load16_s", 0, -128),
+            ("load16_u", 0, 0xFF80),
+            ("load16_s", 2, 0x1234),
+            ("load16_u", 2, 0x1234),
+        ];
+
+        for (name, addr, want) in tests {
+            let result = runtime.call(name, vec![Value::I32(addr)])?;
+            assert_eq!(result, Some(Value::I32(want)), "{name}({addr})")
+        }
+
+        assert!(runtime.call("load8_u", vec![Value::I32(65536)]).is_err());
+        assert!(runtime.call("load16_u", vec![Value::I32(65535)]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn execute_i32_narrow_store() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/memory_i32_narrow_store.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let tests = vec![
+            ("store8_load8_u", 0x12345678, 0x78),
+            ("store16_load16_u", 0x12345678, 0x5678),
+        ];
+
+        for (name, value, want) in tests {
+            let args = vec![Value::I32(0), Value::I32(value)];
+            let result = runtime.call(name, args)?;
+            assert_eq!(result, Some(Value::I32(want)))
+        }
+        // 書き込んだバイト以外は変更されない
+        assert_eq!(runtime.store.memory.data[0..4], [0x78, 0x56, 0x00, 0x00]);
+
+        let args = vec![Value::I32(65535), Value::I32(1)];
+        assert!(runtime.call("store16_load16_u", args).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn execute_i64_narrow_load_store() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/memory_i64_narrow.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let tests = vec![
+            ("store8_load8_s", 0x80, -128),
+            ("store8_load8_u", 0x80, 128),
+            ("store8_load8_u", 0x1234, 0x34),
+            ("store16_load16_s", 0x8000, -32768),
+            ("store16_load16_u", 0x8000, 0x8000),
+            ("store16_load16_u", 0x12345678, 0x5678),
+            ("store32_load32_s", 0x8000_0000, -0x8000_0000),
+            ("store32_load32_u", 0x8000_0000, 0x8000_0000),
+            ("store32_load32_u", -1, 0xFFFF_FFFF),
+            ("store32_load32_s", 0x1_2345_6789, 0x2345_6789),
+        ];
+
+        for (name, value, want) in tests {
+            let args = vec![Value::I32(0), Value::I64(value)];
+            let result = runtime.call(name, args)?;
+            assert_eq!(result, Some(Value::I64(want)), "{name}({value:#x})")
+        }
+
+        let args = vec![Value::I32(65533), Value::I64(1)];
+        assert!(runtime.call("store32_load32_u", args).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn execute_block() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/block.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let tests = vec![("block_result", 4), ("block_void", 4), ("block_param", 6)];
+
+        for (name, want) in tests {
+            let args = vec![Value::I32(1), Value::I32(2)];
+            let result = runtime.call(name, args)?;
+            assert_eq!(result, Some(Value::I32(want)), "{name}")
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn execute_loop() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/loop.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let args = vec![Value::I32(1), Value::I32(2)];
+        let result = runtime.call("loop_once", args)?;
+        assert_eq!(result, Some(Value::I32(4)));
+        Ok(())
+    }
+
+    #[test]
+    fn execute_if_else() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/if_else.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let tests = vec![
+            ("if_else", 1, 10),
+            ("if_else", 0, 20),
+            ("if_only", 1, 30),
+            ("if_only", 0, 0),
+        ];
+
+        for (name, cond, want) in tests {
+            let result = runtime.call(name, vec![Value::I32(cond)])?;
+            assert_eq!(result, Some(Value::I32(want)), "{name}({cond})")
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn execute_factorial() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_factorial.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let tests = vec![(0, 1), (1, 1), (5, 120), (10, 3628800)];
+
+        for (arg, want) in tests {
+            let result = runtime.call("factorial", vec![Value::I64(arg)])?;
+            assert_eq!(result, Some(Value::I64(want)))
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn execute_br() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/br.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let tests = vec![("br_outer", 42), ("br_inner", 0), ("br_func", 5)];
+
+        for (name, want) in tests {
+            let result = runtime.call(name, vec![])?;
+            assert_eq!(result, Some(Value::I32(want)), "{name}")
+        }
+        assert!(runtime.stack.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn execute_br_if() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/br_if.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let tests = vec![
+            ("sum", 10, 55),
+            ("sum", 1, 1),
+            ("count", 5, 5),
+            ("count", 1, 1),
+            ("br_if_not_taken", 0, 2),
+            ("br_if_not_taken", 1, 1),
+        ];
+
+        for (name, arg, want) in tests {
+            let result = runtime.call(name, vec![Value::I32(arg)])?;
+            assert_eq!(result, Some(Value::I32(want)), "{name}({arg})")
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn execute_br_table() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/br_table.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let tests = vec![(0, 100), (1, 200), (2, 300), (3, 400), (-1, 400)];
+
+        for (arg, want) in tests {
+            let result = runtime.call("switch", vec![Value::I32(arg)])?;
+            assert_eq!(result, Some(Value::I32(want)), "switch({arg})")
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn execute_unreachable() -> Result<()> {
+        let wasm = wat::parse_str(
+            r#"(module
+                (func (export "unreachable") unreachable)
+                (func (export "nop") (result i32) nop (i32.const 1) nop)
+            )"#,
+        )?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let err = runtime.call("unreachable", vec![]).unwrap_err();
+        assert!(err.to_string().contains("unreachable executed"));
+        assert!(runtime.call_stack.is_empty());
+
+        assert_eq!(runtime.call("nop", vec![])?, Some(Value::I32(1)));
+        Ok(())
+    }
+
+    #[test]
+    fn execute_trap_kind() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_export_i32div.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let tests = vec![
+            (1, 0, Trap::DivideByZero),
+            (i32::MIN, -1, Trap::IntegerOverflow),
+        ];
+
+        for (lhs, rhs, want) in tests {
+            let args = vec![Value::I32(lhs), Value::I32(rhs)];
+            let err = runtime.call("div_s", args).unwrap_err();
+            assert_eq!(err.downcast_ref::<Trap>(), Some(&want));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn execute_multi_value() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/multi_value.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+
+        let result = runtime.call_multi("pair", vec![])?;
+        assert_eq!(result, vec![Value::I32(1), Value::I32(2)]);
+
+        let args = vec![Value::I32(3), Value::I32(4)];
+        let result = runtime.call_multi("swap", args)?;
+        assert_eq!(result, vec![Value::I32(4), Value::I32(3)]);
+
+        let result = runtime.call_multi("none", vec![])?;
+        assert_eq!(result, vec![]);
+        Ok(())
+    }
+
+    #[test]
+    fn execute_start() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/start.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let result = runtime.call("get", vec![])?;
+        assert_eq!(result, Some(Value::I32(42)));
+        Ok(())
+    }
+
+    #[test]
+    fn execute_start_trap() -> Result<()> {
+        let wasm = wat::parse_str("(module (func unreachable) (start 0))")?;
+        let Err(err) = Runtime::instantiate(wasm) else {
+            panic!("start function should trap");
+        };
+        assert_eq!(err.downcast_ref::<Trap>(), Some(&Trap::Unreachable));
+        Ok(())
+    }
+
+    #[test]
+    fn execute_add_type_mismatch() -> Result<()> {
+        let mut runtime = global_counter_runtime(true);
+        let FuncInst::Internal(func) = runtime.store.funcs[0].clone() else {
+            panic!("inc should be an internal function");
+        };
+        // 引数の検査を経由せずに、i32のグローバル変数にi64の引数を足す
+        runtime.stack.push(Value::I64(1));
+        runtime.push_frame(0, &func)?;
+        let err = runtime.execute().unwrap_err();
+        assert_eq!(err.to_string(), "type mismatch: expected i32, found i64");
+        Ok(())
+    }
+
+    #[test]
+    fn execute_call_indirect() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/call_indirect.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+
+        let tests = vec![(0, 13), (1, 7)];
+        for (idx, want) in tests {
+            let args = vec![Value::I32(10), Value::I32(3), Value::I32(idx)];
+            let result = runtime.call("dispatch", args)?;
+            assert_eq!(result, Some(Value::I32(want)));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn execute_call_indirect_trap() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/call_indirect.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+
+        let tests = vec![
+            (2, Trap::IndirectCallTypeMismatch),
+            (3, Trap::UndefinedElement),
+            (4, Trap::UndefinedElement),
+        ];
+        for (idx, want) in tests {
+            let args = vec![Value::I32(10), Value::I32(3), Value::I32(idx)];
+            let err = runtime.call("dispatch", args).unwrap_err();
+            assert_eq!(err.downcast_ref::<Trap>(), Some(&want));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn execute_float_params() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_float_params.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+
+        let result = runtime.call("f64_id", vec![Value::F64(3.5)])?;
+        assert_eq!(result, Some(Value::F64(3.5)));
+
+        let args = vec![Value::F32(1.5), Value::F64(2.5)];
+        let result = runtime.call("f32_local", args)?;
+        assert_eq!(result, Some(Value::F32(0.0)));
+        Ok(())
+    }
+
+    #[test]
+    fn execute_float_const() -> Result<()> {
+        let wasm = wat::parse_str(
+            r#"(module
+                (func (export "f32") (result f32) f32.const -0.25)
+                (func (export "f64") (result f64) f64.const 1.5))"#,
+        )?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        assert_eq!(runtime.call("f32", vec![])?, Some(Value::F32(-0.25)));
+        assert_eq!(runtime.call("f64", vec![])?, Some(Value::F64(1.5)));
+        Ok(())
+    }
+
+    #[test]
+    fn execute_float_arith() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_export_float_arith.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+
+        let tests = vec![
+            ("f32_add", 1.5, 2.25, 3.75),
+            ("f32_sub", 1.5, 2.25, -0.75),
+            ("f32_mul", 1.5, -2.0, -3.0),
+            ("f32_div", 1.0, 4.0, 0.25),
+        ];
+        for (name, lhs, rhs, want) in tests {
+            let args = vec![Value::F32(lhs), Value::F32(rhs)];
+            let result = runtime.call(name, args)?;
+            assert_eq!(result, Some(Value::F32(want)));
+        }
+
+        let tests = vec![
+            ("f64_add", 1.5, 2.25, 3.75),
+            ("f64_sub", 1.5, 2.25, -0.75),
+            ("f64_mul", 1.5, -2.0, -3.0),
+            ("f64_div", 1.0, 4.0, 0.25),
+            ("f64_div", 1.0, 0.0, f64::INFINITY),
+            ("f64_div", -1.0, 0.0, f64::NEG_INFINITY),
+        ];
+        for (name, lhs, rhs, want) in tests {
+            let args = vec![Value::F64(lhs), Value::F64(rhs)];
+            let result = runtime.call(name, args)?;
+            assert_eq!(result, Some(Value::F64(want)));
+        }
+
+        // 0.0/0.0はNaNになる
+        let args = vec![Value::F64(0.0), Value::F64(0.0)];
+        let Some(Value::F64(result)) = runtime.call("f64_div", args)? else {
+            panic!("expected f64 result");
+        };
+        assert!(result.is_nan());
+        Ok(())
+    }
+
+    #[test]
+    fn execute_float_unary() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_export_float_unary.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+
+        let tests = vec![
+            ("f32_abs", -1.5, 1.5),
+            ("f32_neg", 1.5, -1.5),
+            ("f32_ceil", 1.25, 2.0),
+            ("f32_floor", -1.25, -2.0),
+            ("f32_trunc", -1.75, -1.0),
+            ("f32_nearest", 2.5, 2.0),
+            ("f32_nearest", 3.5, 4.0),
+            ("f32_nearest", -0.5, -0.0),
+            ("f32_sqrt", 6.25, 2.5),
+        ];
+        for (name, arg, want) in tests {
+            let result = runtime.call(name, vec![Value::F32(arg)])?;
+            assert_eq!(result, Some(Value::F32(want)), "{name}({arg})");
+        }
+
+        let tests = vec![
+            ("f64_abs", -1.5, 1.5),
+            ("f64_neg", 1.5, -1.5),
+            ("f64_ceil", 1.25, 2.0),
+            ("f64_floor", -1.25, -2.0),
+            ("f64_trunc", -1.75, -1.0),
+            ("f64_nearest", 2.5, 2.0),
+            ("f64_nearest", 3.5, 4.0),
+            ("f64_nearest", 1.4, 1.0),
+            ("f64_sqrt", 6.25, 2.5),
+        ];
+        for (name, arg, want) in tests {
+            let result = runtime.call(name, vec![Value::F64(arg)])?;
+            assert_eq!(result, Some(Value::F64(want)), "{name}({arg})");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn execute_float_cmp() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_export_float_cmp.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+
+        let tests = vec![
+            ("f32_eq", 1.0, 1.0, 1),
+            ("f32_ne", 1.0, 1.0, 0),
+            ("f32_lt", 1.0, 2.0, 1),
+            ("f32_gt", 1.0, 2.0, 0),
+            ("f32_le", 2.0, 2.0, 1),
+            ("f32_ge", 1.0, 2.0, 0),
+            ("f32_eq", f32::NAN, f32::NAN, 0),
+            ("f32_ne", f32::NAN, f32::NAN, 1),
+            ("f32_lt", f32::NAN, 1.0, 0),
+            ("f32_ge", 1.0, f32::NAN, 0),
+        ];
+        for (name, lhs, rhs, want) in tests {
+            let args = vec![Value::F32(lhs), Value::F32(rhs)];
+            let result = runtime.call(name, args)?;
+            assert_eq!(result, Some(Value::I32(want)), "{name}({lhs}, {rhs})");
+        }
+
+        let tests = vec![
+            ("f64_eq", 1.0, 1.0, 1),
+            ("f64_ne", 1.0, 2.0, 1),
+            ("f64_lt", -1.0, 1.0, 1),
+            ("f64_gt", 2.0, 1.0, 1),
+            ("f64_le", 3.0, 2.0, 0),
+            ("f64_ge", 2.0, 2.0, 1),
+            ("f64_eq", f64::NAN, f64::NAN, 0),
+            ("f64_ne", f64::NAN, f64::NAN, 1),
+            ("f64_lt", f64::NAN, 1.0, 0),
+            ("f64_gt", f64::NAN, 1.0, 0),
+            ("f64_le", 1.0, f64::NAN, 0),
+        ];
+        for (name, lhs, rhs, want) in tests {
+            let args = vec![Value::F64(lhs), Value::F64(rhs)];
+            let result = runtime.call(name, args)?;
+            assert_eq!(result, Some(Value::I32(want)), "{name}({lhs}, {rhs})");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn execute_i32_wrap_i64() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_export_conversion.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+
+        let tests = vec![(0x1_0000_0001, 1), (-1, -1), (0xFFFF_FFFF, -1)];
+        for (arg, want) in tests {
+            let result = runtime.call("i32_wrap_i64", vec![Value::I64(arg)])?;
+            assert_eq!(result, Some(Value::I32(want)));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn execute_i64_extend_i32() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_export_conversion.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+
+        let tests = vec![
+            ("i64_extend_i32_s", -1, -1),
+            ("i64_extend_i32_u", -1, 0xFFFF_FFFF),
+            ("i64_extend_i32_s", i32::MIN, i32::MIN as i64),
+            ("i64_extend_i32_u", i32::MIN, 0x8000_0000),
+        ];
+        for (name, arg, want) in tests {
+            let result = runtime.call(name, vec![Value::I32(arg)])?;
+            assert_eq!(result, Some(Value::I64(want)), "{name}({arg})");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn execute_trunc_float() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_export_conversion.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+
+        let tests = vec![
+            ("i32_trunc_f32_s", Value::F32(3.9), Value::I32(3)),
+            ("i32_trunc_f32_s", Value::F32(-3.9), Value::I32(-3)),
+            ("i32_trunc_f32_u", Value::F32(3.9), Value::I32(3)),
+            ("i32_trunc_f32_u", Value::F32(-0.9), Value::I32(0)),
+            (
+                "i32_trunc_f64_s",
+                Value::F64(-2147483648.9),
+                Value::I32(i32::MIN),
+            ),
+            ("i32_trunc_f64_u", Value::F64(4294967295.9), Value::I32(-1)),
+            (
+                "i64_trunc_f32_s",
+                Value::F32(-1e10),
+                Value::I64(-10000000000),
+            ),
+            ("i64_trunc_f32_u", Value::F32(1e10), Value::I64(10000000000)),
+            ("i64_trunc_f64_s", Value::F64(-9.5), Value::I64(-9)),
+            (
+                "i64_trunc_f64_u",
+                Value::F64((u64::MAX - 2047) as f64),
+                Value::I64(-2048),
+            ),
+        ];
+        for (name, arg, want) in tests {
+            let result = runtime.call(name, vec![arg])?;
+            assert_eq!(result, Some(want), "{name}({arg:?})");
+        }
+
+        let tests = vec![
+            (
+                "i32_trunc_f32_s",
+                Value::F32(f32::NAN),
+                Trap::InvalidConversionToInteger,
+            ),
+            ("i32_trunc_f32_s", Value::F32(1e30), Trap::IntegerOverflow),
+            (
+                "i32_trunc_f32_s",
+                Value::F32(2147483648.0),
+                Trap::IntegerOverflow,
+            ),
+            ("i32_trunc_f32_u", Value::F32(-1.0), Trap::IntegerOverflow),
+            (
+                "i32_trunc_f64_s",
+                Value::F64(2147483648.0),
+                Trap::IntegerOverflow,
+            ),
+            (
+                "i32_trunc_f64_u",
+                Value::F64(f64::INFINITY),
+                Trap::IntegerOverflow,
+            ),
+            (
+                "i64_trunc_f32_s",
+                Value::F32(f32::NEG_INFINITY),
+                Trap::IntegerOverflow,
+            ),
+            (
+                "i64_trunc_f32_u",
+                Value::F32(f32::NAN),
+                Trap::InvalidConversionToInteger,
+            ),
+            (
+                "i64_trunc_f64_s",
+                Value::F64(9223372036854775808.0),
+                Trap::IntegerOverflow,
+            ),
+            (
+                "i64_trunc_f64_u",
+                Value::F64(18446744073709551616.0),
+                Trap::IntegerOverflow,
+            ),
+        ];
+        for (name, arg, want) in tests {
+            let err = runtime.call(name, vec![arg]).unwrap_err();
+            assert_eq!(err.downcast_ref::<Trap>(), Some(&want), "{name}({arg:?})");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn execute_convert_int() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_export_conversion.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+
+        let tests = vec![
+            ("f32_convert_i32_s", Value::I32(-1), Value::F32(-1.0)),
+            (
+                "f32_convert_i32_u",
+                Value::I32(-1),
+                Value::F32(4294967296.0),
+            ),
+            ("f32_convert_i64_s", Value::I64(-1), Value::F32(-1.0)),
+            (
+                "f32_convert_i64_u",
+                Value::I64(-1),
+                Value::F32(18446744073709551616.0),
+            ),
+            ("f64_convert_i32_s", Value::I32(-1), Value::F64(-1.0)),
+            (
+                "f64_convert_i32_u",
+                Value::I32(-1),
+                Value::F64(4294967295.0),
+            ),
+            (
+                "f64_convert_i64_s",
+                Value::I64(i64::MIN),
+                Value::F64(-9223372036854775808.0),
+            ),
+            (
+                "f64_convert_i64_u",
+                Value::I64(i64::MIN),
+                Value::F64(9223372036854775808.0),
+            ),
+        ];
+        for (name, arg, want) in tests {
+            let result = runtime.call(name, vec![arg])?;
+            assert_eq!(result, Some(want), "{name}({arg:?})");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn execute_demote_promote() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_export_conversion.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+
+        let result = runtime.call("f64_promote_f32", vec![Value::F32(1.5)])?;
+        assert_eq!(result, Some(Value::F64(1.5)));
+        let result = runtime.call("f32_demote_f64", vec![Value::F64(1.5)])?;
+        assert_eq!(result, Some(Value::F32(1.5)));
+
+        // 往復しても元の値に戻る
+        let result = runtime.call("round_trip_f32", vec![Value::F32(1.5)])?;
+        assert_eq!(result, Some(Value::F32(1.5)));
+
+        let result = runtime.call("f32_demote_f64", vec![Value::F64(1e300)])?;
+        assert_eq!(result, Some(Value::F32(f32::INFINITY)));
+
+        let Some(Value::F64(result)) =
+            runtime.call("f64_promote_f32", vec![Value::F32(f32::NAN)])?
+        else {
+            panic!("expected f64 result");
+        };
+        assert!(result.is_nan());
+        Ok(())
+    }
+
+    #[test]
+    fn execute_reinterpret() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_export_conversion.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+
+        let tests = vec![
+            (
+                "i32_reinterpret_f32",
+                Value::F32(1.0),
+                Value::I32(0x3F800000),
+            ),
+            (
+                "i32_reinterpret_f32",
+                Value::F32(-0.0),
+                Value::I32(i32::MIN),
+            ),
+            (
+                "i64_reinterpret_f64",
+                Value::F64(1.0),
+                Value::I64(0x3FF0000000000000),
+            ),
+            (
+                "f32_reinterpret_i32",
+                Value::I32(0x40490000),
+                Value::F32(3.140625),
+            ),
+            (
+                "f64_reinterpret_i64",
+                Value::I64(0x4004000000000000),
+                Value::F64(2.5),
+            ),
+        ];
+        for (name, arg, want) in tests {
+            let result = runtime.call(name, vec![arg])?;
+            assert_eq!(result, Some(want), "{name}({arg:?})");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn execute_sign_extension() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_export_conversion.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+
+        let tests = vec![
+            ("i32_extend8_s", Value::I32(0xFF), Value::I32(-1)),
+            ("i32_extend8_s", Value::I32(0x17F), Value::I32(0x7F)),
+            ("i32_extend16_s", Value::I32(0x8000), Value::I32(-32768)),
+            ("i64_extend8_s", Value::I64(0x80), Value::I64(-128)),
+            ("i64_extend16_s", Value::I64(0x1_7FFF), Value::I64(0x7FFF)),
+            ("i64_extend32_s", Value::I64(0xFFFF_FFFF), Value::I64(-1)),
+        ];
+        for (name, arg, want) in tests {
+            let result = runtime.call(name, vec![arg])?;
+            assert_eq!(result, Some(want), "{name}({arg:?})");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn execute_func_after_import() -> Result<()> {
+        let wasm = wat::parse_str(
+            r#"(module
+                (import "env" "add" (func (param i32 i32) (result i32)))
+                (func (export "one") (result i32) (i32.const 1)))"#,
+        )?;
+        let mut imports = Imports::new();
+        imports.add_func("env", "add", add_type(), |_, _| Ok(Some(Value::I32(0))));
+        let mut runtime = Runtime::instantiate_with_imports(wasm, imports)?;
+        // インポートした関数の分だけインデックスがずれる
+        assert_eq!(runtime.call("one", vec![])?, Some(Value::I32(1)));
+        Ok(())
+    }
+
+    #[test]
+    fn execute_host_func() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/import_func.wat")?;
+        let mut imports = Imports::new();
+        imports.add_func("env", "add", add_type(), |_, args| {
+            let [Value::I32(lhs), Value::I32(rhs)] = args[..] else {
+                bail!("invalid args: {:?}", args);
+            };
+            Ok(Some(Value::I32(lhs + rhs)))
+        });
+        let mut runtime = Runtime::instantiate_with_imports(wasm, imports)?;
+
+        let result = runtime.call("add_one", vec![Value::I32(41)])?;
+        assert_eq!(result, Some(Value::I32(42)));
+        // インポートした関数をそのままエクスポートして呼び出す
+        let result = runtime.call("add", vec![Value::I32(2), Value::I32(3)])?;
+        assert_eq!(result, Some(Value::I32(5)));
+        Ok(())
+    }
+
+    #[test]
+    fn execute_host_func_reentrant() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/import_func.wat")?;
+        let mut imports = Imports::new();
+        // ホスト関数からwasmの関数を呼び出す
+        imports.add_func("env", "add", add_type(), |runtime, args| {
+            let result = runtime.call("double", vec![args[0]])?;
+            Ok(Some(Value::I32(
+                i32::try_from(result.unwrap())? + i32::try_from(args[1])?,
+            )))
+        });
+        let mut runtime = Runtime::instantiate_with_imports(wasm, imports)?;
+
+        let result = runtime.call("add_one", vec![Value::I32(20)])?;
+        assert_eq!(result, Some(Value::I32(41)));
+        Ok(())
+    }
+
+    #[test]
+    fn execute_host_func_reentrant_trap() -> Result<()> {
+        let wasm = wat::parse_str(
+            r#"(module
+                (import "env" "f" (func $f (result i32)))
+                (func (export "trap") unreachable)
+                (func (export "outer") (result i32) (i32.add (i32.const 1) (call $f)))
+            )"#,
+        )?;
+        let mut imports = Imports::new();
+        // ホスト関数の中でトラップしても、呼び出し元のフレームはそのまま残る
+        let func_type = FuncType {
+            params: vec![],
+            results: vec![ValueType::I32],
+        };
+        imports.add_func("env", "f", func_type, |runtime, _| {
+            assert!(runtime.call("trap", vec![]).is_err());
+            Ok(Some(Value::I32(41)))
+        });
+        let mut runtime = Runtime::instantiate_with_imports(wasm, imports)?;
+        assert_eq!(runtime.call("outer", vec![])?, Some(Value::I32(42)));
+        assert!(runtime.stack.is_empty());
+        assert!(runtime.call_stack.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn execute_missing_host_func() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/import_func.wat")?;
+        // 登録していないホスト関数はインスタンス化の時点でエラーになる
+        let Err(err) = Runtime::instantiate(wasm) else {
+            panic!("instantiate should fail");
+        };
+        assert!(err.to_string().contains("env.add"), "{err}");
+        Ok(())
+    }
+
+    #[test]
+    fn execute_host_func_invalid_result() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/import_func.wat")?;
+        let mut imports = Imports::new();
+        imports.add_func("env", "add", add_type(), |_, _| Ok(Some(Value::I64(1))));
+        let mut runtime = Runtime::instantiate_with_imports(wasm, imports)?;
+        let err = runtime.call("add_one", vec![Value::I32(1)]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "host function env.add returned Some(I64(1)), expected [I32]"
+        );
+
+        let wasm = wat::parse_file("src/fixtures/import_func.wat")?;
+        let mut imports = Imports::new();
+        imports.add_func("env", "add", add_type(), |_, _| Ok(None));
+        let mut runtime = Runtime::instantiate_with_imports(wasm, imports)?;
+        assert!(runtime.call("add_one", vec![Value::I32(1)]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn host_memory_access() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/memory_sum.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+
+        runtime.write_memory(16, &[1, 2, 3, 4, 250])?;
+        runtime.call("sum", vec![Value::I32(16), Value::I32(5), Value::I32(0)])?;
+        assert_eq!(runtime.read_memory(0, 4)?, &260_i32.to_le_bytes());
+
+        // 範囲外のアクセスはエラーになる
+        let size = runtime.store.memory.data.len();
+        let err = runtime.read_memory(size - 1, 2).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<Trap>(),
+            Some(&Trap::OutOfBoundsMemoryAccess)
+        );
+        let err = runtime.write_memory(usize::MAX, &[0]).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<Trap>(),
+            Some(&Trap::OutOfBoundsMemoryAccess)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn instantiate_invalid_module() -> Result<()> {
+        let wasm =
+            wat::parse_str("(module (func (result i64) (i64.add (i32.const 1) (i32.const 2))))")?;
+        let Err(err) = Runtime::instantiate(wasm) else {
+            panic!("invalid module should not be instantiated");
+        };
+        assert!(err.to_string().contains("type mismatch"), "{err}");
+        Ok(())
+    }
+
+    #[test]
+    fn frame_shares_instructions() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_fib.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        assert_eq!(
+            runtime.call("fib", vec![Value::I32(20)])?,
+            Some(Value::I32(6765))
+        );
+
+        // フレームを作っても命令列は複製されない
+        let FuncInst::Internal(func) = runtime.store.funcs[0].clone() else {
+            panic!("fib should be an internal function");
+        };
+        runtime.stack.push(Value::I32(1));
+        runtime.push_frame(0, &func)?;
+        runtime.stack.push(Value::I32(1));
+        runtime.push_frame(0, &func)?;
+        assert!(Rc::ptr_eq(&runtime.call_stack[0].insts, &func.code.body));
+        assert!(Rc::ptr_eq(&runtime.call_stack[1].insts, &func.code.body));
+        Ok(())
+    }
+
+    #[test]
+    fn execute_nested_blocks() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/nested_blocks.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+
+        // 0..nの中で、偶数なら+2、奇数なら+1を足す
+        let tests = vec![(0, 0), (1, 2), (4, 6), (5, 8)];
+        for (arg, want) in tests {
+            let result = runtime.call("nested", vec![Value::I32(arg)])?;
+            assert_eq!(result, Some(Value::I32(want)), "nested({arg})");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn instantiate_file() -> Result<()> {
+        let mut runtime = Runtime::instantiate_file("src/fixtures/func_fib.wat")?;
+        assert_eq!(
+            runtime.call("fib", vec![Value::I32(10)])?,
+            Some(Value::I32(55))
+        );
+
+        let mut runtime = Runtime::instantiate_file("src/fixtures/func_add.wasm")?;
+        let result = runtime.call_by_func_index(0, vec![Value::I32(1), Value::I32(2)])?;
+        assert_eq!(result, vec![Value::I32(3)]);
+        Ok(())
+    }
+
+    #[test]
+    fn list_exports() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_export_start_i64add.wat")?;
+        let runtime = Runtime::instantiate(wasm)?;
+        assert_eq!(runtime.exported_functions(), vec!["_start".to_string()]);
+        let exports: Vec<_> = runtime.exports().collect();
+        assert_eq!(exports, vec![("_start", &ExportDesc::Func(0))]);
+
+        let wasm = wat::parse_file("src/fixtures/import_func.wat")?;
+        let mut imports = Imports::new();
+        imports.add_func("env", "add", add_type(), |_, _| Ok(Some(Value::I32(0))));
+        let runtime = Runtime::instantiate_with_imports(wasm, imports)?;
+        assert_eq!(
+            runtime.exported_functions(),
+            vec!["add", "add_one", "double"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn export_func_type() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_export_start_i64add.wat")?;
+        let runtime = Runtime::instantiate(wasm)?;
+        assert_eq!(
+            runtime.func_type("_start")?,
+            &FuncType {
+                params: vec![ValueType::I64, ValueType::I64],
+                results: vec![ValueType::I64],
+            }
+        );
+        assert!(runtime.func_type("not_found").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn call_with_invalid_args() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_export_start_i64add.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+
+        let err = runtime.call("_start", vec![Value::I64(1)]).unwrap_err();
+        assert_eq!(err.to_string(), "expected 2 arguments, found 1");
+
+        let args = vec![Value::I64(1), Value::I32(2)];
+        let err = runtime.call("_start", args).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "type mismatch in argument 1: expected I64, found I32"
+        );
+
+        // エラーの後もスタックは壊れていない
+        assert!(runtime.stack.is_empty());
+        let args = vec![Value::I64(1), Value::I64(2)];
+        assert_eq!(runtime.call("_start", args)?, Some(Value::I64(3)));
+        Ok(())
+    }
+
+    #[test]
+    fn call_stack_exhausted() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_recursion.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let err = runtime.call("loop", vec![Value::I32(0)]).unwrap_err();
+        assert_eq!(err.downcast_ref::<Trap>(), Some(&Trap::CallStackExhausted));
+
+        // 上限を変更できる
+        runtime.reset();
+        runtime.max_call_depth = 10;
+        let err = runtime.call("loop", vec![Value::I32(0)]).unwrap_err();
+        assert_eq!(err.downcast_ref::<Trap>(), Some(&Trap::CallStackExhausted));
+        Ok(())
+    }
+
+    #[test]
+    fn trap_backtrace() -> Result<()> {
+        let wasm = wat::parse_str(
+            r#"(module
+                (func $inner unreachable)
+                (func $outer (export "outer") (call $inner))
+            )"#,
+        )?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let err = runtime.call("outer", vec![]).unwrap_err();
+        assert_eq!(err.downcast_ref::<Trap>(), Some(&Trap::Unreachable));
+        // 内側の関数から順に並ぶ
+        let backtrace = err.downcast_ref::<Backtrace>().expect("backtrace");
+        assert_eq!(backtrace.frames, vec![0, 1]);
+        assert_eq!(err.to_string(), "unreachable executed");
+        assert_eq!(
+            format!("{err:#}"),
+            "unreachable executed: wasm backtrace:\n  0: func[0]\n  1: func[1]"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn call_by_index() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_add.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let args = vec![Value::I32(2), Value::I32(3)];
+        assert_eq!(runtime.call_by_index(0, args)?, Some(Value::I32(5)));
+
+        let err = runtime.call_by_index(1, vec![]).unwrap_err();
+        assert_eq!(err.to_string(), "function index 1 out of range");
+        Ok(())
+    }
+
+    #[test]
+    fn reset_after_trap() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_export_i32div.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        // 実行途中の状態が残っていても、resetすれば呼び出しを続けられる
+        runtime.stack.push(Value::I32(1));
+        runtime.call_stack.push(Default::default());
+        runtime.reset();
+        assert!(runtime.stack.is_empty());
+        assert!(runtime.call_stack.is_empty());
+
+        let args = vec![Value::I32(1), Value::I32(0)];
+        assert!(runtime.call("div_s", args).is_err());
+        runtime.reset();
+        let args = vec![Value::I32(6), Value::I32(3)];
+        assert_eq!(runtime.call("div_s", args)?, Some(Value::I32(2)));
+        Ok(())
+    }
+
+    #[test]
+    fn trap_restores_store() -> Result<()> {
+        let wasm = wat::parse_str(
+            r#"(module
+                (memory 1)
+                (global (mut i32) (i32.const 1))
+                (func (export "set") (param i32)
+                    (global.set 0 (local.get 0))
+                    (i32.store (i32.const 0) (local.get 0))
+                )
+                (func (export "set_and_trap") (param i32)
+                    (call 0 (local.get 0))
+                    unreachable
+                )
+            )"#,
+        )?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        runtime.call("set", vec![Value::I32(2)])?;
+
+        // デフォルトではトラップするまでに書き換えた値が残る
+        assert!(runtime.call("set_and_trap", vec![Value::I32(3)]).is_err());
+        assert_eq!(runtime.store.globals[0].value, Value::I32(3));
+
+        // rollback_on_trapを有効にすると、トラップした呼び出しで書き換えた値は元に戻る
+        runtime.rollback_on_trap = true;
+        assert!(runtime.call("set_and_trap", vec![Value::I32(4)]).is_err());
+        assert_eq!(runtime.store.globals[0].value, Value::I32(3));
+        assert_eq!(runtime.read_memory(0, 4)?, 3i32.to_le_bytes());
+        Ok(())
+    }
+
+    #[test]
+    fn snapshot_restore() -> Result<()> {
+        let wasm = wat::parse_str(
+            r#"(module
+                (memory 1)
+                (global (mut i32) (i32.const 1))
+                (func (export "set") (param i32)
+                    (global.set 0 (local.get 0))
+                    (i32.store (i32.const 0) (local.get 0))
+                )
+            )"#,
+        )?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        runtime.stack.push(Value::I64(9));
+        let snapshot = runtime.snapshot();
+
+        runtime.stack.clear();
+        runtime.call("set", vec![Value::I32(5)])?;
+        runtime.write_memory(4, &[1, 2, 3, 4])?;
+        assert_eq!(runtime.store.globals[0].value, Value::I32(5));
+
+        runtime.restore(snapshot);
+        assert_eq!(runtime.stack, vec![Value::I64(9)]);
+        assert!(runtime.call_stack.is_empty());
+        assert_eq!(runtime.store.globals[0].value, Value::I32(1));
+        assert_eq!(runtime.read_memory(0, 8)?, [0; 8]);
+        Ok(())
+    }
+
+    #[test]
+    fn execute_negative_const() -> Result<()> {
+        let wasm = wat::parse_str(
+            r#"(module
+                (func (export "neg") (result i32) i32.const -5)
+                (func (export "min") (result i32) i32.const -2147483648)
+            )"#,
+        )?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        assert_eq!(runtime.call("neg", vec![])?, Some(Value::I32(-5)));
+        assert_eq!(runtime.call("min", vec![])?, Some(Value::I32(i32::MIN)));
+        Ok(())
+    }
+
+    #[test]
+    fn execute_memory_fill_copy() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/memory_bulk.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let i32s = |values: &[i32]| values.iter().map(|v| Value::I32(*v)).collect::<Vec<_>>();
+
+        runtime.call("fill", i32s(&[4, 0xAB, 4]))?;
+        assert_eq!(
+            runtime.read_memory(0, 8)?,
+            [0, 0, 0, 0, 0xAB, 0xAB, 0xAB, 0xAB]
+        );
+        runtime.call("copy", i32s(&[16, 4, 4]))?;
+        assert_eq!(
+            runtime.call("load", i32s(&[16]))?,
+            Some(Value::I32(0xABABABABu32 as i32))
+        );
+
+        // 重なった領域のコピー
+        runtime.write_memory(32, &[1, 2, 3, 4])?;
+        runtime.call("copy", i32s(&[33, 32, 3]))?;
+        assert_eq!(runtime.read_memory(32, 4)?, [1, 1, 2, 3]);
+        runtime.call("copy", i32s(&[32, 33, 3]))?;
+        assert_eq!(runtime.read_memory(32, 4)?, [1, 2, 3, 3]);
+
+        let tests = vec![
+            ("fill", i32s(&[65535, 0, 2])),
+            ("copy", i32s(&[0, 65535, 2])),
+            ("copy", i32s(&[65535, 0, 2])),
+        ];
+        for (name, args) in tests {
+            let err = runtime.call(name, args).unwrap_err();
+            assert_eq!(
+                err.downcast_ref::<Trap>(),
+                Some(&Trap::OutOfBoundsMemoryAccess)
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn execute_memory_init_data_drop() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/memory_init.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let i32s = |values: &[i32]| values.iter().map(|v| Value::I32(*v)).collect::<Vec<_>>();
+        assert_eq!(runtime.read_memory(0, 2)?, b"ab");
+
+        runtime.call("init", i32s(&[8, 1, 4]))?;
+        assert_eq!(runtime.read_memory(8, 4)?, b"ello");
+
+        // セグメントの範囲外
+        let err = runtime.call("init", i32s(&[0, 3, 3])).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<Trap>(),
+            Some(&Trap::OutOfBoundsMemoryAccess)
+        );
+
+        // drop後は長さ0のinitだけが成功する
+        runtime.call("drop", vec![])?;
+        runtime.call("init", i32s(&[0, 0, 0]))?;
+        let err = runtime.call("init", i32s(&[0, 0, 1])).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<Trap>(),
+            Some(&Trap::OutOfBoundsMemoryAccess)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn execute_table_get_set() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/table_get_set.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let get = |runtime: &mut Runtime, idx: i32| runtime.call("get", vec![Value::I32(idx)]);
+        assert_eq!(get(&mut runtime, 0)?, Some(Value::FuncRef(Some(0))));
+        assert_eq!(get(&mut runtime, 1)?, Some(Value::FuncRef(None)));
+
+        runtime.call("copy", vec![Value::I32(1), Value::I32(0)])?;
+        assert_eq!(get(&mut runtime, 1)?, Some(Value::FuncRef(Some(0))));
+        runtime.call("set", vec![Value::I32(0), Value::FuncRef(None)])?;
+        assert_eq!(get(&mut runtime, 0)?, Some(Value::FuncRef(None)));
+
+        let err = get(&mut runtime, 2).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<Trap>(),
+            Some(&Trap::OutOfBoundsTableAccess)
+        );
+        let err = runtime
+            .call("set", vec![Value::I32(2), Value::FuncRef(None)])
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<Trap>(),
+            Some(&Trap::OutOfBoundsTableAccess)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn execute_ref() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/ref_func.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let tests = vec![
+            ("is_null_null", Value::I32(1)),
+            ("is_null_func", Value::I32(0)),
+            ("ref_func", Value::FuncRef(Some(0))),
+            ("call_ref", Value::I32(42)),
+        ];
+        for (name, want) in tests {
+            assert_eq!(runtime.call(name, vec![])?, Some(want), "{name}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn execute_trunc_sat() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/func_export_conversion.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+
+        // 範囲外とNaNはトラップせずに丸める
+        let tests = vec![
+            ("i32_trunc_sat_f32_s", Value::F32(-3.9), Value::I32(-3)),
+            ("i32_trunc_sat_f32_s", Value::F32(f32::NAN), Value::I32(0)),
+            (
+                "i32_trunc_sat_f32_s",
+                Value::F32(1e30),
+                Value::I32(i32::MAX),
+            ),
+            (
+                "i32_trunc_sat_f32_s",
+                Value::F32(-1e30),
+                Value::I32(i32::MIN),
+            ),
+            ("i32_trunc_sat_f32_u", Value::F32(-1.0), Value::I32(0)),
+            ("i32_trunc_sat_f32_u", Value::F32(1e30), Value::I32(-1)),
+            (
+                "i32_trunc_sat_f64_s",
+                Value::F64(1e30),
+                Value::I32(i32::MAX),
+            ),
+            ("i32_trunc_sat_f64_u", Value::F64(f64::NAN), Value::I32(0)),
+            (
+                "i64_trunc_sat_f32_s",
+                Value::F32(f32::INFINITY),
+                Value::I64(i64::MAX),
+            ),
+            (
+                "i64_trunc_sat_f32_u",
+                Value::F32(f32::NEG_INFINITY),
+                Value::I64(0),
+            ),
+            (
+                "i64_trunc_sat_f64_s",
+                Value::F64(-1e300),
+                Value::I64(i64::MIN),
+            ),
+            ("i64_trunc_sat_f64_u", Value::F64(1e300), Value::I64(-1)),
+            ("i64_trunc_sat_f64_u", Value::F64(7.5), Value::I64(7)),
+        ];
+        for (name, arg, want) in tests {
+            assert_eq!(runtime.call(name, vec![arg])?, Some(want), "{name}({arg})");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn execute_add_wrapping() -> Result<()> {
+        let wasm = wat::parse_str(
+            r#"(module
+                (func (export "i32_add") (param i32 i32) (result i32)
+                    (i32.add (local.get 0) (local.get 1)))
+                (func (export "i64_add") (param i64 i64) (result i64)
+                    (i64.add (local.get 0) (local.get 1)))
+            )"#,
+        )?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let args = vec![Value::I32(i32::MAX), Value::I32(1)];
+        assert_eq!(runtime.call("i32_add", args)?, Some(Value::I32(i32::MIN)));
+        let args = vec![Value::I64(i64::MIN), Value::I64(-1)];
+        assert_eq!(runtime.call("i64_add", args)?, Some(Value::I64(i64::MAX)));
+        Ok(())
+    }
+
+    #[test]
+    fn execute_end_of_block_and_function() -> Result<()> {
+        // ブロックのendではフレームをpopせず、関数の残りを実行する
+        let wasm = wat::parse_str(
+            r#"(module
+                (func $inner (result i32)
+                    (block (result i32) (i32.const 1))
+                    (loop (result i32) (i32.const 2))
+                    i32.add
+                )
+                (func (export "outer") (result i32)
+                    (block (result i32)
+                        (i32.add (call $inner) (i32.const 10))
+                    )
+                    (i32.const 100)
+                    i32.add
+                )
+            )"#,
+        )?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        assert_eq!(runtime.call("outer", vec![])?, Some(Value::I32(113)));
+        assert!(runtime.call_stack.is_empty());
+        assert!(runtime.stack.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn builder_define_host_func() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/import_log.wat")?;
+        let logs = Rc::new(RefCell::new(vec![]));
+        let sink = logs.clone();
+        let mut runtime = Runtime::builder()
+            .define("env", "log", log_type(), move |_, args| {
+                let Some(Value::I32(value)) = args.first() else {
+                    bail!("invalid args for log: {:?}", args);
+                };
+                sink.borrow_mut().push(*value);
+                Ok(None)
+            })
+            .instantiate(wasm)?;
+
+        assert_eq!(runtime.call("count", vec![Value::I32(3)])?, None);
+        assert_eq!(*logs.borrow(), vec![0, 1, 2]);
+        Ok(())
+    }
+
+    #[test]
+    fn builder_unsatisfied_import() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/import_log.wat")?;
+        let Err(err) = RuntimeBuilder::new()
+            .define("env", "print", log_type(), |_, _| Ok(None))
+            .instantiate(wasm)
+        else {
+            panic!("instantiate should fail");
+        };
+        assert_eq!(err.to_string(), "unsatisfied import: env.log");
+
+        // シグネチャが一致しないホスト関数は使えない
+        let wasm = wat::parse_file("src/fixtures/import_log.wat")?;
+        let func_type = FuncType {
+            params: vec![ValueType::I64],
+            results: vec![],
+        };
+        let Err(err) = RuntimeBuilder::new()
+            .define("env", "log", func_type, |_, _| Ok(None))
+            .instantiate(wasm)
+        else {
+            panic!("instantiate should fail");
+        };
+        assert_eq!(err.to_string(), "incompatible import type: env.log");
+        Ok(())
+    }
+
+    // (i32, i32) -> i32
+    fn add_type() -> FuncType {
+        FuncType {
+            params: vec![ValueType::I32, ValueType::I32],
+            results: vec![ValueType::I32],
+        }
+    }
+
+    // (i32) -> ()
+    fn log_type() -> FuncType {
+        FuncType {
+            params: vec![ValueType::I32],
+            results: vec![],
+        }
+    }
+
+    #[test]
+    fn execute_local_out_of_bounds() -> Result<()> {
+        // 検証を通らない関数本体なのでStoreを直接組み立てる
+        let func = InternalFuncInst {
+            func_type: FuncType {
+                params: vec![ValueType::I32],
+                results: vec![ValueType::I32],
+            },
+            code: Func::new(
+                vec![ValueType::I32],
+                vec![Instruction::LocalGet(5), Instruction::End],
+            )?,
+        };
+        let mut runtime = Runtime {
+            store: Store {
+                funcs: vec![FuncInst::Internal(func)],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let err = runtime.call_by_index(0, vec![Value::I32(1)]).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<Trap>(),
+            Some(&Trap::LocalOutOfBounds { idx: 5, len: 2 })
+        );
+        assert_eq!(err.to_string(), "local index 5 out of bounds (locals: 2)");
+        Ok(())
+    }
+
+    #[test]
+    fn execute_global_const_exprs() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/global_const.wat")?;
+        let mut runtime = Runtime::instantiate(wasm)?;
+        let tests = vec![
+            ("get_f32", Value::F32(1.5)),
+            ("get_f64", Value::F64(-2.25)),
+            ("get_null", Value::FuncRef(None)),
+            ("get_ref", Value::FuncRef(Some(0))),
+        ];
+        for (name, want) in tests {
+            assert_eq!(runtime.call(name, vec![])?, Some(want), "{name}");
         }
         Ok(())
     }
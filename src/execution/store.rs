@@ -1,15 +1,71 @@
+use super::{import::Imports, value::Value};
 use crate::binary::{
     instruction::Instruction,
     module::Module,
-    types::{ExportDesc, FuncType, ValueType},
+    types::{ConstExpr, DataMode, ElementMode, ExportDesc, FuncType, ImportDesc, ValueType},
 };
 use anyhow::{bail, Result};
-use std::collections::HashMap;
+use std::{collections::HashMap, rc::Rc};
+
+pub const PAGE_SIZE: u32 = 65536; // 64KiB
 
 #[derive(Clone)]
 pub struct Func {
     pub locals: Vec<ValueType>,
-    pub body: Vec<Instruction>,
+    pub body: Rc<[Instruction]>, // フレームを作るたびに複製しないように共有する
+    pub block_addrs: Rc<[Option<BlockAddress>]>, // bodyと同じ長さ
+}
+
+// block, loop, ifに対応するelseとendの位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockAddress {
+    pub else_pc: Option<usize>,
+    pub end_pc: usize,
+}
+
+impl Func {
+    pub fn new(locals: Vec<ValueType>, body: Vec<Instruction>) -> Result<Self> {
+        let block_addrs = analyze_blocks(&body)?;
+        Ok(Self {
+            locals,
+            body: body.into(),
+            block_addrs: block_addrs.into(),
+        })
+    }
+}
+
+// 分岐のたびにendを探さなくて済むように、事前に各ブロックのelseとendの位置を求めておく
+fn analyze_blocks(body: &[Instruction]) -> Result<Vec<Option<BlockAddress>>> {
+    let mut addrs = vec![None; body.len()];
+    // 開始位置とelseの位置
+    let mut blocks: Vec<(usize, Option<usize>)> = vec![];
+    for (pc, inst) in body.iter().enumerate() {
+        match inst {
+            Instruction::Block(_) | Instruction::Loop(_) | Instruction::If(_) => {
+                blocks.push((pc, None))
+            }
+            Instruction::Else => {
+                let Some((_, else_pc)) = blocks.last_mut() else {
+                    bail!("not found if of else")
+                };
+                *else_pc = Some(pc);
+            }
+            Instruction::End => {
+                // 関数本体のendは対応するブロックがない
+                if let Some((start, else_pc)) = blocks.pop() {
+                    addrs[start] = Some(BlockAddress {
+                        else_pc,
+                        end_pc: pc,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    if !blocks.is_empty() {
+        bail!("not found end of block")
+    }
+    Ok(addrs)
 }
 
 #[derive(Clone)]
@@ -18,9 +74,51 @@ pub struct InternalFuncInst {
     pub code: Func,
 }
 
+// インポートした関数
+#[derive(Clone)]
+pub struct ExternalFuncInst {
+    pub module: String,
+    pub field: String,
+    pub func_type: FuncType,
+}
+
 #[derive(Clone)]
 pub enum FuncInst {
     Internal(InternalFuncInst),
+    External(ExternalFuncInst),
+}
+
+impl FuncInst {
+    pub fn func_type(&self) -> &FuncType {
+        match self {
+            FuncInst::Internal(func) => &func.func_type,
+            FuncInst::External(func) => &func.func_type,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlobalInst {
+    pub value: Value,
+    pub mutable: bool,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MemoryInst {
+    pub data: Vec<u8>,
+    pub max: Option<u32>, // 最大ページ数
+}
+
+// データセグメントの内容(data.dropした後とactiveセグメントは空になる)
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DataInst {
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TableInst {
+    pub elems: Vec<Option<u32>>, // 関数のインデックス
+    pub max: Option<u32>,        // 最大要素数
 }
 
 pub struct ExportInst {
@@ -30,29 +128,90 @@ pub struct ExportInst {
 
 #[derive(Default)]
 pub struct ModuleInst {
+    pub func_types: Vec<FuncType>,
     pub exports: HashMap<String, ExportInst>,
 }
 
 #[derive(Default)]
 pub struct Store {
     pub funcs: Vec<FuncInst>,
+    pub globals: Vec<GlobalInst>,
+    pub table: TableInst,
+    pub memory: MemoryInst,
+    pub datas: Vec<DataInst>,
     pub module: ModuleInst,
 }
 
 impl Store {
     // Function SectionとCode SectionからStoreに必要な情報を取得
     pub fn new(module: Module) -> Result<Self> {
+        Self::with_imports(module, &Imports::default())
+    }
+
+    // インポートするグローバル変数の値はimportsから取得する
+    pub fn with_imports(module: Module, imports: &Imports) -> Result<Self> {
         let func_type_idxs = match module.function_section {
             Some(ref idxs) => idxs.clone(),
             _ => vec![],
         };
 
         let mut funcs = vec![];
+        let mut globals = vec![];
+
+        // インポートしたものは定義したものより前のインデックスを占める
+        if let Some(ref import_section) = module.import_section {
+            for import in import_section {
+                let type_idx = match import.desc {
+                    ImportDesc::Func(type_idx) => type_idx,
+                    ImportDesc::Global(ref global_type) => {
+                        let Some(value) = imports.get_global(&import.module, &import.field) else {
+                            bail!(
+                                "not found import global: {}.{}",
+                                import.module,
+                                import.field
+                            )
+                        };
+                        if value.ty() != global_type.value_type {
+                            bail!(
+                                "type mismatch in import global {}.{}: expected {:?}, found {:?}",
+                                import.module,
+                                import.field,
+                                global_type.value_type,
+                                value.ty()
+                            )
+                        }
+                        globals.push(GlobalInst {
+                            value,
+                            mutable: global_type.mutable,
+                        });
+                        continue;
+                    }
+                    _ => bail!(
+                        "unsupported import: {}.{} ({:?})",
+                        import.module,
+                        import.field,
+                        import.desc
+                    ),
+                };
+                let Some(func_type) = module
+                    .type_section
+                    .as_ref()
+                    .and_then(|types| types.get(type_idx as usize))
+                else {
+                    bail!("not found func type in type_section")
+                };
+                funcs.push(FuncInst::External(ExternalFuncInst {
+                    module: import.module.clone(),
+                    field: import.field.clone(),
+                    func_type: func_type.clone(),
+                }));
+            }
+        }
 
         if let Some(ref code_section) = module.code_section {
-            for (func_body, type_idx) in code_section.iter().zip(func_type_idxs.into_iter()) {
+            for (func_body, type_idx) in code_section.iter().zip(func_type_idxs) {
                 let Some(ref func_types) = module.type_section else {
-                  bail!("not found type_section")
+                    bail!("not found type_section")
                 };
 
                 let Some(func_type) = func_types.get(type_idx as usize) else {
@@ -68,18 +227,122 @@ impl Store {
 
                 let func = FuncInst::Internal(InternalFuncInst {
                     func_type: func_type.clone(),
-                    code: Func {
-                        locals,
-                        body: func_body.code.clone(),
-                    },
+                    code: Func::new(locals, func_body.code.clone())?,
                 });
                 funcs.push(func)
             }
         }
 
+        if let Some(ref global_section) = module.global_section {
+            for global in global_section {
+                // 初期化式を評価して初期値を決める
+                let value = eval_const_expr(&global.init_expr, &globals)?;
+                globals.push(GlobalInst {
+                    value,
+                    mutable: global.global_type.mutable,
+                });
+            }
+        }
+
+        // テーブルは1つまで
+        let mut table = TableInst::default();
+        if let Some(ref table_section) = module.table_section {
+            if let Some(tab) = table_section.first() {
+                table = TableInst {
+                    elems: vec![None; tab.limits.min as usize],
+                    max: tab.limits.max,
+                };
+            }
+        }
+
+        // activeセグメントの関数のインデックスをテーブルに書き込む
+        if let Some(ref element_section) = module.element_section {
+            for element in element_section {
+                let ElementMode::Active {
+                    table_idx,
+                    ref offset,
+                } = element.mode
+                else {
+                    continue;
+                };
+                if table_idx != 0 {
+                    bail!("not found table: {}", table_idx)
+                }
+                let Value::I32(offset) = eval_const_expr(offset, &globals)? else {
+                    bail!("element offset must be i32")
+                };
+                let start = offset as u32 as usize;
+                let Some(elems) = table.elems.get_mut(start..start + element.init.len()) else {
+                    bail!("elements segment does not fit")
+                };
+                for (elem, expr) in elems.iter_mut().zip(&element.init) {
+                    let Value::FuncRef(func_idx) = eval_const_expr(expr, &globals)? else {
+                        bail!("element must be funcref")
+                    };
+                    *elem = func_idx;
+                }
+            }
+        }
+
+        // 線形メモリは1つまで
+        let mut memory = MemoryInst::default();
+        if let Some(ref memory_section) = module.memory_section {
+            if let Some(mem) = memory_section.first() {
+                let min = mem.limits.min as usize * PAGE_SIZE as usize;
+                memory = MemoryInst {
+                    data: vec![0; min],
+                    max: mem.limits.max,
+                };
+            }
+        }
+
+        // activeセグメントは線形メモリに書き込み、passiveセグメントはmemory.init用に残す
+        let mut datas = vec![];
+        for data in module.data_section.iter().flatten() {
+            let DataMode::Active {
+                memory_idx,
+                ref offset,
+            } = data.mode
+            else {
+                datas.push(DataInst {
+                    data: data.init.clone(),
+                });
+                continue;
+            };
+            if memory_idx != 0 {
+                bail!("not found memory: {}", memory_idx)
+            }
+            let Value::I32(offset) = eval_const_expr(offset, &globals)? else {
+                bail!("data offset must be i32")
+            };
+            let start = offset as u32 as usize;
+            let Some(dst) = memory.data.get_mut(start..start + data.init.len()) else {
+                bail!("data segment does not fit")
+            };
+            dst.copy_from_slice(&data.init);
+            datas.push(DataInst::default());
+        }
+
+        let table_count = module.table_section.as_ref().map_or(0, Vec::len);
+        let memory_count = module.memory_section.as_ref().map_or(0, Vec::len);
         let mut exports = HashMap::default();
         if let Some(ref sections) = module.export_section {
             for export in sections {
+                // エクスポートする実体のインデックスが範囲内か確認する
+                let (kind, idx, count) = match export.desc {
+                    ExportDesc::Func(idx) => ("function", idx, funcs.len()),
+                    ExportDesc::Table(idx) => ("table", idx, table_count),
+                    ExportDesc::Memory(idx) => ("memory", idx, memory_count),
+                    ExportDesc::Global(idx) => ("global", idx, globals.len()),
+                };
+                if idx as usize >= count {
+                    bail!(
+                        "invalid export \"{}\": {} index {} out of range",
+                        export.name,
+                        kind,
+                        idx
+                    )
+                }
                 let name = export.name.clone();
                 let export_inst = ExportInst {
                     name: name.clone(),
@@ -88,11 +351,199 @@ impl Store {
                 exports.insert(name, export_inst);
             }
         };
-        let module_inst = ModuleInst { exports };
+        let module_inst = ModuleInst {
+            func_types: module.type_section.clone().unwrap_or_default(),
+            exports,
+        };
 
         Ok(Self {
             funcs,
+            globals,
+            table,
+            memory,
+            datas,
             module: module_inst,
         })
     }
 }
+
+// 初期化式を評価する(global.getはそれまでに初期化したグローバル変数を参照する)
+fn eval_const_expr(expr: &ConstExpr, globals: &[GlobalInst]) -> Result<Value> {
+    let value = match *expr {
+        ConstExpr::I32Const(value) => Value::I32(value),
+        ConstExpr::I64Const(value) => Value::I64(value),
+        ConstExpr::F32Const(value) => Value::F32(value),
+        ConstExpr::F64Const(value) => Value::F64(value),
+        ConstExpr::RefNull(_) => Value::FuncRef(None),
+        ConstExpr::RefFunc(idx) => Value::FuncRef(Some(idx)),
+        ConstExpr::GlobalGet(idx) => {
+            let Some(global) = globals.get(idx as usize) else {
+                bail!("not found global: {}", idx)
+            };
+            global.value
+        }
+    };
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BlockAddress, DataInst, FuncInst, GlobalInst, Store, PAGE_SIZE};
+    use crate::{
+        binary::{
+            module::Module,
+            types::{Export, ExportDesc, ValueType},
+        },
+        execution::{import::Imports, value::Value},
+    };
+    use anyhow::Result;
+
+    #[test]
+    fn init_memory() -> Result<()> {
+        let wasm = wat::parse_str("(module (memory 1 2))")?;
+        let store = Store::new(Module::new(&wasm)?)?;
+        assert_eq!(store.memory.data.len(), PAGE_SIZE as usize);
+        assert!(store.memory.data.iter().all(|b| *b == 0));
+        assert_eq!(store.memory.max, Some(2));
+        Ok(())
+    }
+
+    #[test]
+    fn init_table() -> Result<()> {
+        let wasm = wat::parse_str("(module (table 2 funcref))")?;
+        let store = Store::new(Module::new(&wasm)?)?;
+        assert_eq!(store.table.elems, vec![None, None]);
+        assert_eq!(store.table.max, None);
+        Ok(())
+    }
+
+    #[test]
+    fn init_table_elements() -> Result<()> {
+        let wasm = wat::parse_str(
+            "(module (table 3 funcref) (func $f) (func $g) (elem (i32.const 1) $g $f))",
+        )?;
+        let store = Store::new(Module::new(&wasm)?)?;
+        assert_eq!(store.table.elems, vec![None, Some(1), Some(0)]);
+
+        // passiveとdeclarativeのセグメントはテーブルに書き込まない
+        let wasm = wat::parse_str(
+            "(module (table 2 funcref) (func $f) (elem func $f) (elem declare func $f) (elem (i32.const 1) funcref (ref.null func)))",
+        )?;
+        let store = Store::new(Module::new(&wasm)?)?;
+        assert_eq!(store.table.elems, vec![None, None]);
+        Ok(())
+    }
+
+    #[test]
+    fn init_data() -> Result<()> {
+        let wasm = wat::parse_str(r#"(module (memory 1) (data (i32.const 2) "ab") (data "cd"))"#)?;
+        let store = Store::new(Module::new(&wasm)?)?;
+        assert_eq!(store.memory.data[..4], [0, 0, b'a', b'b']);
+        assert_eq!(
+            store.datas,
+            vec![
+                DataInst::default(),
+                DataInst {
+                    data: b"cd".to_vec()
+                }
+            ]
+        );
+
+        let wasm = wat::parse_str(r#"(module (memory 1) (data (i32.const 65535) "ab"))"#)?;
+        assert!(Store::new(Module::new(&wasm)?).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn init_import_func() -> Result<()> {
+        let wasm = wat::parse_str(
+            r#"(module
+                (import "env" "add" (func (param i32 i32) (result i32)))
+                (func (result i32) (i32.const 1)))"#,
+        )?;
+        let store = Store::new(Module::new(&wasm)?)?;
+        assert_eq!(store.funcs.len(), 2);
+        let FuncInst::External(ref func) = store.funcs[0] else {
+            panic!("func 0 should be imported");
+        };
+        assert_eq!((func.module.as_str(), func.field.as_str()), ("env", "add"));
+        assert_eq!(func.func_type.params, vec![ValueType::I32, ValueType::I32]);
+        assert!(matches!(store.funcs[1], FuncInst::Internal(_)));
+        Ok(())
+    }
+
+    #[test]
+    fn init_invalid_export() -> Result<()> {
+        let wasm = wat::parse_str(r#"(module (func (export "f")))"#)?;
+        let mut module = Module::new(&wasm)?;
+        // 存在しない関数をエクスポートしている
+        module.export_section = Some(vec![Export {
+            name: "g".to_string(),
+            desc: ExportDesc::Func(1),
+        }]);
+        let Err(err) = Store::new(module) else {
+            panic!("export of unknown function should be rejected");
+        };
+        assert_eq!(
+            err.to_string(),
+            "invalid export \"g\": function index 1 out of range"
+        );
+
+        let wasm = wat::parse_str(r#"(module (memory 1) (export "mem" (memory 0)))"#)?;
+        let mut module = Module::new(&wasm)?;
+        assert!(Store::new(Module::new(&wasm)?).is_ok());
+        module.export_section = Some(vec![Export {
+            name: "mem".to_string(),
+            desc: ExportDesc::Memory(1),
+        }]);
+        let Err(err) = Store::new(module) else {
+            panic!("export of unknown memory should be rejected");
+        };
+        assert_eq!(
+            err.to_string(),
+            "invalid export \"mem\": memory index 1 out of range"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn analyze_block_address() -> Result<()> {
+        let wasm = wat::parse_str(
+            "(module (func (block (loop (br 1))) (if (i32.const 1) (then (block)) (else nop))))",
+        )?;
+        let store = Store::new(Module::new(&wasm)?)?;
+        let FuncInst::Internal(ref func) = store.funcs[0] else {
+            panic!("func 0 should be internal");
+        };
+        // 0: block, 1: loop, 2: br 1, 3: end, 4: end, 5: i32.const 1, 6: if,
+        // 7: block, 8: end, 9: else, 10: nop, 11: end, 12: end
+        let block = |else_pc, end_pc| Some(BlockAddress { else_pc, end_pc });
+        assert_eq!(func.code.block_addrs[0], block(None, 4));
+        assert_eq!(func.code.block_addrs[1], block(None, 3));
+        assert_eq!(func.code.block_addrs[6], block(Some(9), 11));
+        assert_eq!(func.code.block_addrs[7], block(None, 8));
+        assert_eq!(func.code.block_addrs[2], None);
+        Ok(())
+    }
+
+    #[test]
+    fn init_import_global() -> Result<()> {
+        let wasm = wat::parse_str(
+            r#"(module
+                (global $base (import "env" "base") i32)
+                (global $ptr i32 (global.get $base)))"#,
+        )?;
+        let mut imports = Imports::new();
+        imports.add_global("env", "base", Value::I32(1024));
+        let store = Store::with_imports(Module::new(&wasm)?, &imports)?;
+        let global = GlobalInst {
+            value: Value::I32(1024),
+            mutable: false,
+        };
+        assert_eq!(store.globals, vec![global.clone(), global]);
+
+        // 値を登録していないグローバル変数はインポートできない
+        assert!(Store::new(Module::new(&wasm)?).is_err());
+        Ok(())
+    }
+}
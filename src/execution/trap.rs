@@ -0,0 +1,58 @@
+use std::fmt;
+
+// 実行時に発生するトラップ
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Trap {
+    DivideByZero,
+    IntegerOverflow,
+    OutOfBoundsMemoryAccess,
+    OutOfBoundsTableAccess,
+    Unreachable,
+    StackUnderflow,
+    UndefinedElement,
+    IndirectCallTypeMismatch,
+    InvalidConversionToInteger,
+    CallStackExhausted,
+    LocalOutOfBounds { idx: u32, len: usize }, // 参照したローカル変数のインデックスと個数
+}
+
+impl fmt::Display for Trap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            Trap::DivideByZero => "integer divide by zero",
+            Trap::IntegerOverflow => "integer overflow",
+            Trap::OutOfBoundsMemoryAccess => "out of bounds memory access",
+            Trap::OutOfBoundsTableAccess => "out of bounds table access",
+            Trap::Unreachable => "unreachable executed",
+            Trap::StackUnderflow => "stack underflow",
+            Trap::UndefinedElement => "undefined element",
+            Trap::IndirectCallTypeMismatch => "indirect call type mismatch",
+            Trap::InvalidConversionToInteger => "invalid conversion to integer",
+            Trap::CallStackExhausted => "call stack exhausted",
+            Trap::LocalOutOfBounds { idx, len } => {
+                return write!(f, "local index {} out of bounds (locals: {})", idx, len)
+            }
+        };
+        write!(f, "{}", message)
+    }
+}
+
+impl std::error::Error for Trap {}
+
+// トラップが発生した時点のコールスタック(内側の関数から順に関数インデックスを並べる)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Backtrace {
+    pub frames: Vec<u32>,
+}
+
+impl fmt::Display for Backtrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "wasm backtrace:")?;
+        for (i, func_idx) in self.frames.iter().enumerate() {
+            write!(f, "\n  {}: func[{}]", i, func_idx)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Backtrace {}
@@ -1,7 +1,76 @@
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use crate::binary::types::ValueType;
+use std::fmt;
+
+// 浮動小数点数はEqを実装しないのでPartialEqのみ
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Value {
     I32(i32),
     I64(i64),
+    F32(f32),
+    F64(f64),
+    FuncRef(Option<u32>), // 関数のインデックス(Noneはnull参照)
+}
+
+impl Value {
+    pub fn ty(&self) -> ValueType {
+        match self {
+            Value::I32(_) => ValueType::I32,
+            Value::I64(_) => ValueType::I64,
+            Value::F32(_) => ValueType::F32,
+            Value::F64(_) => ValueType::F64,
+            Value::FuncRef(_) => ValueType::FuncRef,
+        }
+    }
+
+    pub fn as_i32(&self) -> Option<i32> {
+        match self {
+            Value::I32(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Value::I64(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_f32(&self) -> Option<f32> {
+        match self {
+            Value::F32(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::F64(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    // funcrefであればnullかどうかを含めて返す
+    pub fn as_func_ref(&self) -> Option<Option<u32>> {
+        match self {
+            Value::FuncRef(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+// i32以外は型の接尾辞を付ける(例: 42, 42i64, 1.5f64)
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::I32(value) => write!(f, "{}", value),
+            Value::I64(value) => write!(f, "{}i64", value),
+            Value::F32(value) => write!(f, "{}f32", value),
+            Value::F64(value) => write!(f, "{}f64", value),
+            Value::FuncRef(Some(idx)) => write!(f, "funcref({})", idx),
+            Value::FuncRef(None) => write!(f, "funcref(null)"),
+        }
+    }
 }
 
 impl From<i32> for Value {
@@ -16,13 +85,104 @@ impl From<i64> for Value {
     }
 }
 
-impl std::ops::Add for Value {
-    type Output = Self;
-    fn add(self, rhs: Self) -> Self::Output {
-        match (self, rhs) {
-            (Value::I32(left), Value::I32(right)) => Value::I32(left + right),
-            (Value::I64(left), Value::I64(right)) => Value::I64(left + right),
-            _ => panic!("Type mismatch"),
+impl From<f32> for Value {
+    fn from(value: f32) -> Self {
+        Value::F32(value)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value::F64(value)
+    }
+}
+
+// 比較命令の結果はi32の1か0で表す
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::I32(value as i32)
+    }
+}
+
+impl TryFrom<Value> for i32 {
+    type Error = anyhow::Error;
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::I32(value) => Ok(value),
+            _ => anyhow::bail!("type mismatch: expected i32, found {}", value.ty()),
+        }
+    }
+}
+
+impl TryFrom<Value> for i64 {
+    type Error = anyhow::Error;
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::I64(value) => Ok(value),
+            _ => anyhow::bail!("type mismatch: expected i64, found {}", value.ty()),
+        }
+    }
+}
+
+impl TryFrom<Value> for f32 {
+    type Error = anyhow::Error;
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::F32(value) => Ok(value),
+            _ => anyhow::bail!("type mismatch: expected f32, found {}", value.ty()),
+        }
+    }
+}
+
+impl TryFrom<Value> for f64 {
+    type Error = anyhow::Error;
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::F64(value) => Ok(value),
+            _ => anyhow::bail!("type mismatch: expected f64, found {}", value.ty()),
+        }
+    }
+}
+
+impl TryFrom<Value> for Option<u32> {
+    type Error = anyhow::Error;
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::FuncRef(value) => Ok(value),
+            _ => anyhow::bail!("type mismatch: expected funcref, found {}", value.ty()),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Value;
+    use crate::binary::types::ValueType;
+
+    #[test]
+    fn value_accessors() {
+        let value = Value::I64(5);
+        assert_eq!(value.as_i64(), Some(5));
+        assert_eq!(value.as_i32(), None);
+        assert_eq!(value.ty(), ValueType::I64);
+
+        assert_eq!(Value::I32(-1).as_i32(), Some(-1));
+        assert_eq!(Value::F32(1.5).as_f32(), Some(1.5));
+        assert_eq!(Value::F32(1.5).as_f64(), None);
+        assert_eq!(Value::F64(2.5).as_f64(), Some(2.5));
+        assert_eq!(Value::F64(2.5).ty(), ValueType::F64);
+        assert_eq!(Value::FuncRef(None).as_func_ref(), Some(None));
+        assert_eq!(Value::I32(0).as_func_ref(), None);
+        assert_eq!(Value::FuncRef(Some(1)).ty(), ValueType::FuncRef);
+    }
+
+    #[test]
+    fn display_value() {
+        assert_eq!(format!("{}", Value::I32(42)), "42");
+        assert_eq!(format!("{}", Value::I64(7)), "7i64");
+        assert_eq!(format!("{}", Value::F32(-0.5)), "-0.5f32");
+        assert_eq!(format!("{}", Value::F64(1.5)), "1.5f64");
+        assert_eq!(format!("{}", Value::FuncRef(Some(3))), "funcref(3)");
+        assert_eq!(format!("{}", Value::FuncRef(None)), "funcref(null)");
+    }
+}
@@ -0,0 +1,102 @@
+use super::{import::Imports, runtime::Runtime, value::Value};
+use crate::binary::types::{FuncType, ValueType};
+use anyhow::{bail, Result};
+use std::{cell::RefCell, io::Write, rc::Rc};
+
+const WASI_MODULE: &str = "wasi_snapshot_preview1";
+const ERRNO_SUCCESS: i32 = 0;
+const ERRNO_BADF: i32 = 8;
+
+// 出力先はテストなどで差し替えられるようにする
+pub type Output = Rc<RefCell<dyn Write>>;
+
+// WASIの最小限の実装(fd_writeのみ)
+pub struct Wasi {
+    stdout: Output,
+    stderr: Output,
+}
+
+impl Default for Wasi {
+    fn default() -> Self {
+        Self {
+            stdout: Rc::new(RefCell::new(std::io::stdout())),
+            stderr: Rc::new(RefCell::new(std::io::stderr())),
+        }
+    }
+}
+
+impl Wasi {
+    pub fn new(stdout: Output, stderr: Output) -> Self {
+        Self { stdout, stderr }
+    }
+
+    // WASIの関数をインポートに登録する
+    pub fn add_to_imports(&self, imports: &mut Imports) {
+        let stdout = self.stdout.clone();
+        let stderr = self.stderr.clone();
+        // (fd, iovs, iovs_len, nwritten) -> errno
+        let fd_write_type = FuncType {
+            params: vec![ValueType::I32; 4],
+            results: vec![ValueType::I32],
+        };
+        imports.add_func(WASI_MODULE, "fd_write", fd_write_type, move |runtime, args| {
+            let &[Value::I32(fd), Value::I32(iovs), Value::I32(iovs_len), Value::I32(nwritten)] =
+                &args[..]
+            else {
+                bail!("invalid args for fd_write: {:?}", args);
+            };
+            // 1: stdout, 2: stderr
+            let output = match fd {
+                1 => &stdout,
+                2 => &stderr,
+                _ => return Ok(Some(Value::I32(ERRNO_BADF))),
+            };
+            let written = fd_write(runtime, output, iovs as u32, iovs_len as u32)?;
+            runtime.write_memory(nwritten as u32 as usize, &written.to_le_bytes())?;
+            Ok(Some(Value::I32(ERRNO_SUCCESS)))
+        });
+    }
+}
+
+// iovecの配列を順に読み取って書き込み、書き込んだバイト数を返す
+fn fd_write(runtime: &Runtime, output: &Output, iovs: u32, iovs_len: u32) -> Result<u32> {
+    let mut written = 0u32;
+    for i in 0..iovs_len as usize {
+        // iovecは{ buf: u32, buf_len: u32 }の8バイト
+        let iov = runtime.read_memory(iovs as usize + i * 8, 8)?;
+        let buf = u32::from_le_bytes(iov[0..4].try_into()?);
+        let buf_len = u32::from_le_bytes(iov[4..8].try_into()?);
+
+        let bytes = runtime.read_memory(buf as usize, buf_len as usize)?;
+        output.borrow_mut().write_all(bytes)?;
+        written = written.wrapping_add(buf_len);
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Wasi;
+    use crate::execution::{import::Imports, runtime::Runtime, value::Value};
+    use anyhow::Result;
+    use std::{cell::RefCell, rc::Rc};
+
+    #[test]
+    fn fd_write() -> Result<()> {
+        let wasm = wat::parse_file("src/fixtures/wasi_hello.wat")?;
+        let stdout = Rc::new(RefCell::new(vec![]));
+        let stderr = Rc::new(RefCell::new(vec![]));
+
+        let mut imports = Imports::new();
+        Wasi::new(stdout.clone(), stderr.clone()).add_to_imports(&mut imports);
+        let mut runtime = Runtime::instantiate_with_imports(wasm, imports)?;
+
+        let result = runtime.call("_start", vec![])?;
+        assert_eq!(result, Some(Value::I32(0)));
+        assert_eq!(stdout.borrow().as_slice(), b"hi");
+        assert!(stderr.borrow().is_empty());
+        // nwritten_ptrに書き込んだバイト数
+        assert_eq!(runtime.read_memory(20, 4)?, &2_u32.to_le_bytes());
+        Ok(())
+    }
+}
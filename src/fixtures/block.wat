@@ -0,0 +1,31 @@
+(module
+  (func (export "block_result") (param i32 i32) (result i32)
+    (block (result i32)
+      (local.get 0)
+      (local.get 1)
+      i32.add
+    )
+    (local.get 0)
+    i32.add
+  )
+  (func (export "block_void") (param i32 i32) (result i32)
+    (block
+      (local.get 1)
+      (local.set 0)
+    )
+    (local.get 0)
+    (local.get 1)
+    i32.add
+  )
+  (func (export "block_param") (param i32 i32) (result i32)
+    (local.get 0)
+    (local.get 1)
+    (block (param i32 i32) (result i32)
+      i32.add
+      (local.get 0)
+      (local.get 1)
+      i32.add
+      i32.add
+    )
+  )
+)
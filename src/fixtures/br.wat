@@ -0,0 +1,26 @@
+(module
+  (func (export "br_outer") (result i32)
+    (block (result i32)
+      (block
+        (i32.const 8)
+        (i32.const 42)
+        (br 1)
+      )
+      (i32.const 100)
+    )
+  )
+  (func (export "br_inner") (result i32)
+    (local i32)
+    (block
+      (br 0)
+      (i32.const 1)
+      (local.set 0)
+    )
+    (local.get 0)
+  )
+  (func (export "br_func") (result i32)
+    (i32.const 5)
+    (br 0)
+    (i32.const 6)
+  )
+)
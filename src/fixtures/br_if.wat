@@ -0,0 +1,41 @@
+(module
+  (func (export "sum") (param i32) (result i32)
+    (local i32)
+    (loop
+      (local.get 1)
+      (local.get 0)
+      (i32.add)
+      (local.set 1)
+      (local.get 0)
+      (i32.const 1)
+      (i32.sub)
+      (local.tee 0)
+      (br_if 0)
+    )
+    (local.get 1)
+  )
+  (func (export "count") (param i32) (result i32)
+    (local i32)
+    (loop
+      (local.get 1)
+      (i32.const 1)
+      (i32.add)
+      (local.set 1)
+      (local.get 0)
+      (i32.const 1)
+      (i32.sub)
+      (local.tee 0)
+      (br_if 0)
+    )
+    (local.get 1)
+  )
+  (func (export "br_if_not_taken") (param i32) (result i32)
+    (block (result i32)
+      (i32.const 1)
+      (local.get 0)
+      (br_if 0)
+      (local.set 0)
+      (i32.const 2)
+    )
+  )
+)
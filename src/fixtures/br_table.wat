@@ -0,0 +1,21 @@
+(module
+  (func (export "switch") (param i32) (result i32)
+    (block
+      (block
+        (block
+          (block
+            (local.get 0)
+            (br_table 0 1 2 3)
+          )
+          (i32.const 100)
+          (br 3)
+        )
+        (i32.const 200)
+        (br 2)
+      )
+      (i32.const 300)
+      (br 1)
+    )
+    (i32.const 400)
+  )
+)
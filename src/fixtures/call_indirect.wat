@@ -0,0 +1,17 @@
+(module
+  (type $binop (func (param i32 i32) (result i32)))
+  (table 4 funcref)
+  (elem (i32.const 0) $add $sub $neg)
+  (func $add (type $binop)
+    (i32.add (local.get 0) (local.get 1))
+  )
+  (func $sub (type $binop)
+    (i32.sub (local.get 0) (local.get 1))
+  )
+  (func $neg (param i32) (result i32)
+    (i32.sub (i32.const 0) (local.get 0))
+  )
+  (func (export "dispatch") (param i32 i32 i32) (result i32)
+    (call_indirect (type $binop) (local.get 0) (local.get 1) (local.get 2))
+  )
+)
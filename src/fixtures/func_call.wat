@@ -0,0 +1,18 @@
+(module
+  (func (param i64 i64) (result i64)
+    (local.get 0)
+    (local.get 1)
+    i64.add
+  )
+  (func (param i64) (result i64)
+    (local.get 0)
+    (local.get 0)
+    (call 0)
+  )
+  (func (export "triple") (param i64) (result i64)
+    (local.get 0)
+    (call 1)
+    (local.get 0)
+    (call 0)
+  )
+)
@@ -0,0 +1,10 @@
+(module
+  (func (export "i32_eqz") (param i32) (result i32)
+    (local.get 0)
+    i32.eqz
+  )
+  (func (export "i64_eqz") (param i64) (result i32)
+    (local.get 0)
+    i64.eqz
+  )
+)
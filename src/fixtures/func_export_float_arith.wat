@@ -0,0 +1,42 @@
+(module
+  (func (export "f32_add") (param f32 f32) (result f32)
+    (local.get 0)
+    (local.get 1)
+    f32.add
+  )
+  (func (export "f32_sub") (param f32 f32) (result f32)
+    (local.get 0)
+    (local.get 1)
+    f32.sub
+  )
+  (func (export "f32_mul") (param f32 f32) (result f32)
+    (local.get 0)
+    (local.get 1)
+    f32.mul
+  )
+  (func (export "f32_div") (param f32 f32) (result f32)
+    (local.get 0)
+    (local.get 1)
+    f32.div
+  )
+  (func (export "f64_add") (param f64 f64) (result f64)
+    (local.get 0)
+    (local.get 1)
+    f64.add
+  )
+  (func (export "f64_sub") (param f64 f64) (result f64)
+    (local.get 0)
+    (local.get 1)
+    f64.sub
+  )
+  (func (export "f64_mul") (param f64 f64) (result f64)
+    (local.get 0)
+    (local.get 1)
+    f64.mul
+  )
+  (func (export "f64_div") (param f64 f64) (result f64)
+    (local.get 0)
+    (local.get 1)
+    f64.div
+  )
+)
@@ -0,0 +1,62 @@
+(module
+  (func (export "f32_eq") (param f32 f32) (result i32)
+    (local.get 0)
+    (local.get 1)
+    f32.eq
+  )
+  (func (export "f32_ne") (param f32 f32) (result i32)
+    (local.get 0)
+    (local.get 1)
+    f32.ne
+  )
+  (func (export "f32_lt") (param f32 f32) (result i32)
+    (local.get 0)
+    (local.get 1)
+    f32.lt
+  )
+  (func (export "f32_gt") (param f32 f32) (result i32)
+    (local.get 0)
+    (local.get 1)
+    f32.gt
+  )
+  (func (export "f32_le") (param f32 f32) (result i32)
+    (local.get 0)
+    (local.get 1)
+    f32.le
+  )
+  (func (export "f32_ge") (param f32 f32) (result i32)
+    (local.get 0)
+    (local.get 1)
+    f32.ge
+  )
+  (func (export "f64_eq") (param f64 f64) (result i32)
+    (local.get 0)
+    (local.get 1)
+    f64.eq
+  )
+  (func (export "f64_ne") (param f64 f64) (result i32)
+    (local.get 0)
+    (local.get 1)
+    f64.ne
+  )
+  (func (export "f64_lt") (param f64 f64) (result i32)
+    (local.get 0)
+    (local.get 1)
+    f64.lt
+  )
+  (func (export "f64_gt") (param f64 f64) (result i32)
+    (local.get 0)
+    (local.get 1)
+    f64.gt
+  )
+  (func (export "f64_le") (param f64 f64) (result i32)
+    (local.get 0)
+    (local.get 1)
+    f64.le
+  )
+  (func (export "f64_ge") (param f64 f64) (result i32)
+    (local.get 0)
+    (local.get 1)
+    f64.ge
+  )
+)
@@ -0,0 +1,58 @@
+(module
+  (func (export "f32_abs") (param f32) (result f32)
+    (local.get 0)
+    f32.abs
+  )
+  (func (export "f32_neg") (param f32) (result f32)
+    (local.get 0)
+    f32.neg
+  )
+  (func (export "f32_ceil") (param f32) (result f32)
+    (local.get 0)
+    f32.ceil
+  )
+  (func (export "f32_floor") (param f32) (result f32)
+    (local.get 0)
+    f32.floor
+  )
+  (func (export "f32_trunc") (param f32) (result f32)
+    (local.get 0)
+    f32.trunc
+  )
+  (func (export "f32_nearest") (param f32) (result f32)
+    (local.get 0)
+    f32.nearest
+  )
+  (func (export "f32_sqrt") (param f32) (result f32)
+    (local.get 0)
+    f32.sqrt
+  )
+  (func (export "f64_abs") (param f64) (result f64)
+    (local.get 0)
+    f64.abs
+  )
+  (func (export "f64_neg") (param f64) (result f64)
+    (local.get 0)
+    f64.neg
+  )
+  (func (export "f64_ceil") (param f64) (result f64)
+    (local.get 0)
+    f64.ceil
+  )
+  (func (export "f64_floor") (param f64) (result f64)
+    (local.get 0)
+    f64.floor
+  )
+  (func (export "f64_trunc") (param f64) (result f64)
+    (local.get 0)
+    f64.trunc
+  )
+  (func (export "f64_nearest") (param f64) (result f64)
+    (local.get 0)
+    f64.nearest
+  )
+  (func (export "f64_sqrt") (param f64) (result f64)
+    (local.get 0)
+    f64.sqrt
+  )
+)
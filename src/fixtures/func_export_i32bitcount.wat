@@ -0,0 +1,14 @@
+(module
+  (func (export "clz") (param i32) (result i32)
+    (local.get 0)
+    i32.clz
+  )
+  (func (export "ctz") (param i32) (result i32)
+    (local.get 0)
+    i32.ctz
+  )
+  (func (export "popcnt") (param i32) (result i32)
+    (local.get 0)
+    i32.popcnt
+  )
+)
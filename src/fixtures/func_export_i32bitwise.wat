@@ -0,0 +1,17 @@
+(module
+  (func (export "and") (param i32 i32) (result i32)
+    (local.get 0)
+    (local.get 1)
+    i32.and
+  )
+  (func (export "or") (param i32 i32) (result i32)
+    (local.get 0)
+    (local.get 1)
+    i32.or
+  )
+  (func (export "xor") (param i32 i32) (result i32)
+    (local.get 0)
+    (local.get 1)
+    i32.xor
+  )
+)
@@ -0,0 +1,42 @@
+(module
+  (func (export "lt_s") (param i32 i32) (result i32)
+    (local.get 0)
+    (local.get 1)
+    i32.lt_s
+  )
+  (func (export "lt_u") (param i32 i32) (result i32)
+    (local.get 0)
+    (local.get 1)
+    i32.lt_u
+  )
+  (func (export "gt_s") (param i32 i32) (result i32)
+    (local.get 0)
+    (local.get 1)
+    i32.gt_s
+  )
+  (func (export "gt_u") (param i32 i32) (result i32)
+    (local.get 0)
+    (local.get 1)
+    i32.gt_u
+  )
+  (func (export "le_s") (param i32 i32) (result i32)
+    (local.get 0)
+    (local.get 1)
+    i32.le_s
+  )
+  (func (export "le_u") (param i32 i32) (result i32)
+    (local.get 0)
+    (local.get 1)
+    i32.le_u
+  )
+  (func (export "ge_s") (param i32 i32) (result i32)
+    (local.get 0)
+    (local.get 1)
+    i32.ge_s
+  )
+  (func (export "ge_u") (param i32 i32) (result i32)
+    (local.get 0)
+    (local.get 1)
+    i32.ge_u
+  )
+)
@@ -0,0 +1,12 @@
+(module
+  (func (export "div_s") (param i32 i32) (result i32)
+    (local.get 0)
+    (local.get 1)
+    i32.div_s
+  )
+  (func (export "div_u") (param i32 i32) (result i32)
+    (local.get 0)
+    (local.get 1)
+    i32.div_u
+  )
+)
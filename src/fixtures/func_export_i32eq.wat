@@ -0,0 +1,12 @@
+(module
+  (func (export "eq") (param i32 i32) (result i32)
+    (local.get 0)
+    (local.get 1)
+    i32.eq
+  )
+  (func (export "ne") (param i32 i32) (result i32)
+    (local.get 0)
+    (local.get 1)
+    i32.ne
+  )
+)
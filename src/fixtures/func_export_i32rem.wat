@@ -0,0 +1,12 @@
+(module
+  (func (export "rem_s") (param i32 i32) (result i32)
+    (local.get 0)
+    (local.get 1)
+    i32.rem_s
+  )
+  (func (export "rem_u") (param i32 i32) (result i32)
+    (local.get 0)
+    (local.get 1)
+    i32.rem_u
+  )
+)
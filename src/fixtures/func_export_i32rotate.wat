@@ -0,0 +1,12 @@
+(module
+  (func (export "rotl") (param i32 i32) (result i32)
+    (local.get 0)
+    (local.get 1)
+    i32.rotl
+  )
+  (func (export "rotr") (param i32 i32) (result i32)
+    (local.get 0)
+    (local.get 1)
+    i32.rotr
+  )
+)
@@ -0,0 +1,27 @@
+(module
+  (func (export "sub") (param i64 i64) (result i64)
+    (local.get 0)
+    (local.get 1)
+    i64.sub
+  )
+  (func (export "div_s") (param i64 i64) (result i64)
+    (local.get 0)
+    (local.get 1)
+    i64.div_s
+  )
+  (func (export "div_u") (param i64 i64) (result i64)
+    (local.get 0)
+    (local.get 1)
+    i64.div_u
+  )
+  (func (export "rem_s") (param i64 i64) (result i64)
+    (local.get 0)
+    (local.get 1)
+    i64.rem_s
+  )
+  (func (export "rem_u") (param i64 i64) (result i64)
+    (local.get 0)
+    (local.get 1)
+    i64.rem_u
+  )
+)
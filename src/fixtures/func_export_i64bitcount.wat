@@ -0,0 +1,14 @@
+(module
+  (func (export "clz") (param i64) (result i64)
+    (local.get 0)
+    i64.clz
+  )
+  (func (export "ctz") (param i64) (result i64)
+    (local.get 0)
+    i64.ctz
+  )
+  (func (export "popcnt") (param i64) (result i64)
+    (local.get 0)
+    i64.popcnt
+  )
+)
@@ -0,0 +1,42 @@
+(module
+  (func (export "and") (param i64 i64) (result i64)
+    (local.get 0)
+    (local.get 1)
+    i64.and
+  )
+  (func (export "or") (param i64 i64) (result i64)
+    (local.get 0)
+    (local.get 1)
+    i64.or
+  )
+  (func (export "xor") (param i64 i64) (result i64)
+    (local.get 0)
+    (local.get 1)
+    i64.xor
+  )
+  (func (export "shl") (param i64 i64) (result i64)
+    (local.get 0)
+    (local.get 1)
+    i64.shl
+  )
+  (func (export "shr_s") (param i64 i64) (result i64)
+    (local.get 0)
+    (local.get 1)
+    i64.shr_s
+  )
+  (func (export "shr_u") (param i64 i64) (result i64)
+    (local.get 0)
+    (local.get 1)
+    i64.shr_u
+  )
+  (func (export "rotl") (param i64 i64) (result i64)
+    (local.get 0)
+    (local.get 1)
+    i64.rotl
+  )
+  (func (export "rotr") (param i64 i64) (result i64)
+    (local.get 0)
+    (local.get 1)
+    i64.rotr
+  )
+)
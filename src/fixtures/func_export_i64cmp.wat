@@ -0,0 +1,52 @@
+(module
+  (func (export "eq") (param i64 i64) (result i32)
+    (local.get 0)
+    (local.get 1)
+    i64.eq
+  )
+  (func (export "ne") (param i64 i64) (result i32)
+    (local.get 0)
+    (local.get 1)
+    i64.ne
+  )
+  (func (export "lt_s") (param i64 i64) (result i32)
+    (local.get 0)
+    (local.get 1)
+    i64.lt_s
+  )
+  (func (export "lt_u") (param i64 i64) (result i32)
+    (local.get 0)
+    (local.get 1)
+    i64.lt_u
+  )
+  (func (export "gt_s") (param i64 i64) (result i32)
+    (local.get 0)
+    (local.get 1)
+    i64.gt_s
+  )
+  (func (export "gt_u") (param i64 i64) (result i32)
+    (local.get 0)
+    (local.get 1)
+    i64.gt_u
+  )
+  (func (export "le_s") (param i64 i64) (result i32)
+    (local.get 0)
+    (local.get 1)
+    i64.le_s
+  )
+  (func (export "le_u") (param i64 i64) (result i32)
+    (local.get 0)
+    (local.get 1)
+    i64.le_u
+  )
+  (func (export "ge_s") (param i64 i64) (result i32)
+    (local.get 0)
+    (local.get 1)
+    i64.ge_s
+  )
+  (func (export "ge_u") (param i64 i64) (result i32)
+    (local.get 0)
+    (local.get 1)
+    i64.ge_u
+  )
+)
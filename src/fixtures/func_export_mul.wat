@@ -0,0 +1,12 @@
+(module
+  (func (export "i32_mul") (param i32 i32) (result i32)
+    (local.get 0)
+    (local.get 1)
+    i32.mul
+  )
+  (func (export "i64_mul") (param i64 i64) (result i64)
+    (local.get 0)
+    (local.get 1)
+    i64.mul
+  )
+)
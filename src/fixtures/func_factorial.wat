@@ -0,0 +1,19 @@
+(module
+  (func (export "factorial") (param i64) (result i64)
+    (local.get 0)
+    (i64.eqz)
+    (if (result i64)
+      (then
+        (i64.const 1)
+      )
+      (else
+        (local.get 0)
+        (local.get 0)
+        (i64.const 1)
+        (i64.sub)
+        (call 0)
+        (i64.mul)
+      )
+    )
+  )
+)
@@ -0,0 +1,9 @@
+(module
+  (func (export "f64_id") (param f64) (result f64)
+    (local.get 0)
+  )
+  (func (export "f32_local") (param f32 f64) (result f32)
+    (local f32)
+    (local.get 2)
+  )
+)
@@ -0,0 +1,9 @@
+(module
+  (func (export "tee") (param i32) (result i32)
+    (local i32)
+    (local.get 0)
+    (local.tee 1)
+    (local.get 1)
+    i32.add
+  )
+)
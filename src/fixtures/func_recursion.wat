@@ -0,0 +1,5 @@
+(module
+  (func $loop (export "loop") (param $n i32) (result i32)
+    (call $loop (i32.add (local.get $n) (i32.const 1)))
+  )
+)
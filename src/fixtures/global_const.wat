@@ -0,0 +1,11 @@
+(module
+  (func $f)
+  (global $f32 f32 (f32.const 1.5))
+  (global $f64 f64 (f64.const -2.25))
+  (global $null funcref (ref.null func))
+  (global $ref funcref (ref.func $f))
+  (func (export "get_f32") (result f32) (global.get $f32))
+  (func (export "get_f64") (result f64) (global.get $f64))
+  (func (export "get_null") (result funcref) (global.get $null))
+  (func (export "get_ref") (result funcref) (global.get $ref))
+)
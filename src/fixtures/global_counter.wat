@@ -0,0 +1,10 @@
+(module
+  (global (mut i32) (i32.const 7))
+  (func (export "inc") (param i32) (result i32)
+    (global.get 0)
+    (local.get 0)
+    i32.add
+    (global.set 0)
+    (global.get 0)
+  )
+)
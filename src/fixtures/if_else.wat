@@ -0,0 +1,24 @@
+(module
+  (func (export "if_else") (param i32) (result i32)
+    (local.get 0)
+    (if (result i32)
+      (then
+        (i32.const 10)
+      )
+      (else
+        (i32.const 20)
+      )
+    )
+  )
+  (func (export "if_only") (param i32) (result i32)
+    (local i32)
+    (local.get 0)
+    (if
+      (then
+        (i32.const 30)
+        (local.set 1)
+      )
+    )
+    (local.get 1)
+  )
+)
@@ -0,0 +1,10 @@
+(module
+  (import "env" "add" (func $add (param i32 i32) (result i32)))
+  (export "add" (func $add))
+  (func (export "add_one") (param i32) (result i32)
+    (call $add (local.get 0) (i32.const 1))
+  )
+  (func (export "double") (param i32) (result i32)
+    (i32.add (local.get 0) (local.get 0))
+  )
+)
@@ -0,0 +1,11 @@
+(module
+  (import "env" "log" (func $log (param i32)))
+  (func (export "count") (param i32)
+    (local $i i32)
+    (loop $next
+      (call $log (local.get $i))
+      (local.set $i (i32.add (local.get $i) (i32.const 1)))
+      (br_if $next (i32.lt_s (local.get $i) (local.get 0)))
+    )
+  )
+)
@@ -0,0 +1,13 @@
+(module
+  (func (export "loop_once") (param i32 i32) (result i32)
+    (loop (result i32)
+      (block (result i32)
+        (local.get 0)
+        (local.get 1)
+        i32.add
+      )
+    )
+    (local.get 0)
+    i32.add
+  )
+)
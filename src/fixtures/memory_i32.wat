@@ -0,0 +1,10 @@
+(module
+  (memory 1)
+  (func (export "store_load") (param i32 i32) (result i32)
+    (local.get 0)
+    (local.get 1)
+    (i32.store)
+    (local.get 0)
+    (i32.load)
+  )
+)
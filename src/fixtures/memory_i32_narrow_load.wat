@@ -0,0 +1,19 @@
+(module
+  (memory 1)
+  (func (export "load8_s") (param i32) (result i32)
+    (local.get 0)
+    i32.load8_s
+  )
+  (func (export "load8_u") (param i32) (result i32)
+    (local.get 0)
+    i32.load8_u
+  )
+  (func (export "load16_s") (param i32) (result i32)
+    (local.get 0)
+    i32.load16_s
+  )
+  (func (export "load16_u") (param i32) (result i32)
+    (local.get 0)
+    i32.load16_u
+  )
+)
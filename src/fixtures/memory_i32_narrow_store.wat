@@ -0,0 +1,17 @@
+(module
+  (memory 1)
+  (func (export "store8_load8_u") (param i32 i32) (result i32)
+    (local.get 0)
+    (local.get 1)
+    (i32.store8)
+    (local.get 0)
+    (i32.load8_u)
+  )
+  (func (export "store16_load16_u") (param i32 i32) (result i32)
+    (local.get 0)
+    (local.get 1)
+    (i32.store16)
+    (local.get 0)
+    (i32.load16_u)
+  )
+)
@@ -0,0 +1,10 @@
+(module
+  (memory 1)
+  (func (export "store_load") (param i32 i64) (result i64)
+    (local.get 0)
+    (local.get 1)
+    (i64.store)
+    (local.get 0)
+    (i64.load)
+  )
+)
@@ -0,0 +1,45 @@
+(module
+  (memory 1)
+  (func (export "store8_load8_s") (param i32 i64) (result i64)
+    (local.get 0)
+    (local.get 1)
+    (i64.store8)
+    (local.get 0)
+    (i64.load8_s)
+  )
+  (func (export "store8_load8_u") (param i32 i64) (result i64)
+    (local.get 0)
+    (local.get 1)
+    (i64.store8)
+    (local.get 0)
+    (i64.load8_u)
+  )
+  (func (export "store16_load16_s") (param i32 i64) (result i64)
+    (local.get 0)
+    (local.get 1)
+    (i64.store16)
+    (local.get 0)
+    (i64.load16_s)
+  )
+  (func (export "store16_load16_u") (param i32 i64) (result i64)
+    (local.get 0)
+    (local.get 1)
+    (i64.store16)
+    (local.get 0)
+    (i64.load16_u)
+  )
+  (func (export "store32_load32_s") (param i32 i64) (result i64)
+    (local.get 0)
+    (local.get 1)
+    (i64.store32)
+    (local.get 0)
+    (i64.load32_s)
+  )
+  (func (export "store32_load32_u") (param i32 i64) (result i64)
+    (local.get 0)
+    (local.get 1)
+    (i64.store32)
+    (local.get 0)
+    (i64.load32_u)
+  )
+)
@@ -0,0 +1,11 @@
+(module
+  (memory 1)
+  (data (i32.const 0) "ab")
+  (data $passive "hello")
+  (func (export "init") (param i32 i32 i32)
+    (memory.init $passive (local.get 0) (local.get 1) (local.get 2))
+  )
+  (func (export "drop")
+    (data.drop $passive)
+  )
+)
@@ -0,0 +1,18 @@
+(module
+  (memory 1)
+  ;; [ptr, ptr+len)のバイトの合計をdstに書き込む
+  (func (export "sum") (param $ptr i32) (param $len i32) (param $dst i32)
+    (local $total i32)
+    (block $done
+      (loop $next
+        (br_if $done (i32.eqz (local.get $len)))
+        (local.set $total
+          (i32.add (local.get $total) (i32.load8_u (local.get $ptr))))
+        (local.set $ptr (i32.add (local.get $ptr) (i32.const 1)))
+        (local.set $len (i32.sub (local.get $len) (i32.const 1)))
+        (br $next)
+      )
+    )
+    (i32.store (local.get $dst) (local.get $total))
+  )
+)
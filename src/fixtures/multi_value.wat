@@ -0,0 +1,18 @@
+(module
+  (func (export "pair") (result i32 i32)
+    (i32.const 1)
+    (i32.const 2)
+  )
+  (func (export "swap") (param i32 i32) (result i32 i32)
+    (block (result i32 i32)
+      (i32.const 0)
+      (local.get 1)
+      (local.get 0)
+      (br 0)
+    )
+  )
+  (func (export "none")
+    (i32.const 1)
+    (br 0)
+  )
+)
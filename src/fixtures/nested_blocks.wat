@@ -0,0 +1,26 @@
+(module
+  (func (export "nested") (param $n i32) (result i32)
+    (local $i i32)
+    (local $sum i32)
+    (block $exit
+      (loop $continue
+        (br_if $exit (i32.ge_s (local.get $i) (local.get $n)))
+        (local.set $sum
+          (i32.add
+            (local.get $sum)
+            (block $add (result i32)
+              (if (i32.rem_u (local.get $i) (i32.const 2))
+                (then (br $add (i32.const 1))))
+              (block (result i32)
+                (i32.const 2)
+              )
+            )
+          )
+        )
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $continue)
+      )
+    )
+    (local.get $sum)
+  )
+)
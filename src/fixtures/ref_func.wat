@@ -0,0 +1,20 @@
+(module
+  (table 1 funcref)
+  (func $f (result i32) (i32.const 42))
+  (elem declare func $f)
+  (elem (i32.const 0) funcref (ref.null func))
+  (func (export "is_null_null") (result i32)
+    (ref.is_null (ref.null func))
+  )
+  (func (export "is_null_func") (result i32)
+    (ref.is_null (ref.func $f))
+  )
+  (func (export "ref_func") (result funcref)
+    (ref.func $f)
+  )
+  ;; テーブルに書き込んだ参照で関数を呼び出す
+  (func (export "call_ref") (result i32)
+    (table.set 0 (i32.const 0) (ref.func $f))
+    (call_indirect (result i32) (i32.const 0))
+  )
+)
@@ -0,0 +1,10 @@
+(module
+  (global (mut i32) (i32.const 0))
+  (func
+    (global.set 0 (i32.const 42))
+  )
+  (func (export "get") (result i32)
+    (global.get 0)
+  )
+  (start 0)
+)
@@ -0,0 +1,14 @@
+(module
+  (table 2 funcref)
+  (func $f (result i32) (i32.const 1))
+  (elem (i32.const 0) $f)
+  (func (export "get") (param i32) (result funcref)
+    (table.get 0 (local.get 0))
+  )
+  (func (export "set") (param i32 funcref)
+    (table.set 0 (local.get 0) (local.get 1))
+  )
+  (func (export "copy") (param i32 i32)
+    (table.set 0 (local.get 0) (table.get 0 (local.get 1)))
+  )
+)
@@ -0,0 +1,14 @@
+(module
+  (import "wasi_snapshot_preview1" "fd_write"
+    (func $fd_write (param i32 i32 i32 i32) (result i32)))
+  (memory 1)
+  (func (export "_start") (result i32)
+    ;; 0番地に"hi"
+    (i32.store8 (i32.const 0) (i32.const 0x68))
+    (i32.store8 (i32.const 1) (i32.const 0x69))
+    ;; 8番地にiovec { buf: 0, buf_len: 2 }
+    (i32.store (i32.const 8) (i32.const 0))
+    (i32.store (i32.const 12) (i32.const 2))
+    (call $fd_write (i32.const 1) (i32.const 8) (i32.const 1) (i32.const 20))
+  )
+)
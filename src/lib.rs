@@ -1,2 +1,8 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
 pub mod binary;
+// インタプリタは浮動小数点演算やWASIでstdを使う
+#[cfg(feature = "std")]
 pub mod execution;